@@ -0,0 +1,463 @@
+// Terminal frontend for playing over SSH or anywhere else eframe can't open a window.
+// Input is read a line at a time, so it also works with piped stdin.
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use crossterm::cursor::MoveTo;
+use crossterm::style::{Color, Stylize};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::execute;
+
+use shadow_soldiers_gui::dice::{take_seed_arg, DiceState};
+use shadow_soldiers_gui::dialogues::{Content, DEFAULT_CONTENT_DIR};
+use shadow_soldiers_gui::engine::{ChallengeResult, Engine, GameEvent, OptionView};
+use shadow_soldiers_gui::history::{HistoryEntry, HISTORY_EXPORT_FILE};
+use shadow_soldiers_gui::player::Player;
+use shadow_soldiers_gui::save::{self, SaveGame, AUTOSAVE_SLOT, SAVE_DIR};
+
+const SIDEBAR_WIDTH: usize = 30;
+
+const HELP: &str =
+    "1-9 choose an option, i inventory, k skills, m map, h [text] history, e export history, s <name> save, l [name] load, d <name> delete, q quit";
+
+struct Tui {
+    engine: Engine,
+    messages: Vec<String>, // What the last command did, shown under the dialogue
+    check: Option<ChallengeResult>, // The skill check the last choice made, shown above the dialogue it led to
+}
+
+impl Tui {
+    fn new(content: Content) -> Self {
+        Self { engine: Engine::new(content.clone(), Player::new(&content.skills)), messages: vec![], check: None }
+    }
+
+    fn handle_events(&mut self, events: &[GameEvent]) {
+        for event in events {
+            if let GameEvent::Challenge(result) = event {
+                roll_animation(result.dice);
+                self.check = Some(result.clone());
+                continue;
+            }
+            self.messages.extend(event.to_string().lines().map(str::to_string));
+            if let GameEvent::LocationChanged(_) = event {
+                if let Err(err) = save::save(SAVE_DIR, AUTOSAVE_SLOT, &SaveGame::from_engine(&self.engine)) {
+                    self.messages.push(format!("Autosave failed: {}", err));
+                }
+            }
+        }
+    }
+
+    // Returns false if a save couldn't be loaded
+    fn load(&mut self, slot: &str) -> bool {
+        match save::load(SAVE_DIR, slot, &self.engine.content) {
+            Ok(game) => {
+                game.restore(&mut self.engine);
+                self.messages.push(format!("Loaded \"{}\".", slot));
+                true
+            }
+            Err(err) => {
+                self.messages.push(format!("Load failed: {}", err));
+                false
+            }
+        }
+    }
+
+    // Asks for the four stats until they add up, or loads a save instead. None on end of input.
+    fn create_character(&mut self) -> Option<()> {
+        loop {
+            let slots = save::list_slots(SAVE_DIR);
+            let mut header = vec!["Character Creation".bold().to_string(), String::new()];
+            if !slots.is_empty() {
+                header.push(format!("Saved games: {}", slots.join(", ")));
+                header.push("Type a save name to load it, or press Enter to build a new character.".to_string());
+            }
+            header.append(&mut self.messages);
+            draw(&header, &[]);
+
+            if !slots.is_empty() {
+                let slot = prompt("> ")?;
+                if !slot.is_empty() {
+                    if self.load(&slot) {
+                        return Some(());
+                    }
+                    continue;
+                }
+            }
+
+            println!("Distribute 12 points among your four stats. Each stat must have between 1 and 6 points.");
+            let registry = &self.engine.content.skills;
+            for attribute in &registry.attributes {
+                let answer = prompt(&format!("{} [{}]: ", attribute.name, self.engine.player.attribute(&attribute.id)))?;
+                if let Ok(value) = answer.parse() {
+                    self.engine.player.attributes.insert(attribute.id.clone(), value);
+                }
+            }
+
+            if self.engine.player.is_valid(registry) {
+                let events = self.engine.new_game();
+                self.handle_events(&events);
+                return Some(());
+            }
+            self.messages.push(format!(
+                "Ensure all stats are between 1 and 6 points, and the total is exactly 12 (remaining: {}).",
+                self.engine.player.remaining_points(&self.engine.content.skills)
+            ));
+        }
+    }
+
+    fn sidebar(&self) -> Vec<String> {
+        let engine = &self.engine;
+        let player = &engine.player;
+        let location = engine.content.locations.get(&engine.location_id).map_or(engine.location_id.as_str(), |l| l.name.as_str());
+        let mut lines = vec![
+            format!("{}, {}", engine.time, engine.time.phase()).bold().to_string(),
+            location.to_string(),
+            String::new(),
+        ];
+        for attribute in &engine.content.skills.attributes {
+            lines.push(format!("{:<12}{}", attribute.name, player.attribute(&attribute.id)));
+        }
+        lines.extend([
+            String::new(),
+            format!("XP          {}/100", player.xp),
+            format!("Skill points {}", player.skill_points),
+            format!("Items       {}", player.items.len()),
+        ]);
+        lines
+    }
+
+    fn draw_dialogue(&mut self) {
+        let width = main_width();
+        let mut lines = vec![];
+        if let Some(result) = self.check.take() {
+            lines.push(format!("[{}] [{}]  {}", result.dice.0, result.dice.1, result.breakdown()));
+            let banner = match (result.critical, result.success) {
+                (true, true) => " CRITICAL SUCCESS ".on_green(),
+                (true, false) => " CRITICAL FAILURE ".on_red(),
+                (false, true) => " SUCCESS ".on_green(),
+                (false, false) => " FAILURE ".on_red(),
+            };
+            lines.push(banner.white().bold().to_string());
+            lines.push(String::new());
+        }
+
+        let view = self.engine.current_view();
+        match &view {
+            Some(view) => {
+                lines.push(view.speaker.as_str().bold().to_string());
+                lines.push(String::new());
+                lines.extend(wrap(&view.intro, width));
+                lines.push(String::new());
+                for announcement in &view.announcements {
+                    lines.extend(announcement_lines(announcement.speaker.as_deref(), &announcement.text, width));
+                    lines.push(String::new());
+                }
+                for check in &view.passive_checks {
+                    lines.extend(self.interjection(&check.skill, &check.speaker, &check.text, width));
+                    lines.push(String::new());
+                }
+                for (number, option) in view.options.iter().enumerate() {
+                    lines.extend(wrap(&format!("{}. {}", number + 1, option.label()), width));
+                }
+            }
+            None => lines.push("There's nothing here.".to_string()),
+        }
+        if !self.messages.is_empty() {
+            lines.push(String::new());
+            for message in self.messages.drain(..) {
+                lines.extend(wrap(&message, width).into_iter().map(|line| line.dark_grey().to_string()));
+            }
+        }
+        draw(&lines, &self.sidebar());
+    }
+
+    fn inventory(&mut self) -> Option<()> {
+        let mut lines = vec!["Inventory:".bold().to_string()];
+        if self.engine.player.items.is_empty() {
+            lines.push("You have no items.".to_string());
+        }
+        lines.extend(self.engine.player.items.iter().cloned());
+        draw(&lines, &self.sidebar());
+        prompt("Press Enter to return to the game. ")?;
+        Some(())
+    }
+
+    // A skill's voice: its name in its colour, then what it says
+    fn interjection(&self, skill: &str, speaker: &str, text: &str, width: usize) -> Vec<String> {
+        let (r, g, b) = self.engine.content.skills.colour(skill);
+        let name = speaker.to_uppercase();
+        let mut lines = wrap(&format!("{} {}", name, text), width);
+        // The name is at the start of the first line, unless it was too long to share it
+        if let Some(rest) = lines[0].strip_prefix(&name) {
+            lines[0] = format!("{}{}", name.as_str().with(Color::Rgb { r, g, b }).bold(), rest);
+        }
+        lines
+    }
+
+    // The transcript so far, or just the entries mentioning `query`
+    fn history(&mut self, query: &str) -> Option<()> {
+        let width = main_width();
+        let mut lines = vec!["History".bold().to_string()];
+        for entry in self.engine.history.search(query) {
+            match entry {
+                HistoryEntry::Dialogue { speaker, intro, .. } => {
+                    lines.push(String::new());
+                    if !speaker.is_empty() {
+                        lines.push(speaker.as_str().bold().to_string());
+                    }
+                    lines.extend(wrap(intro, width));
+                }
+                HistoryEntry::Passive { skill, speaker, text, .. } => lines.extend(self.interjection(skill, speaker, text, width)),
+                HistoryEntry::Event { speaker, text } => lines.extend(announcement_lines(speaker.as_deref(), text, width)),
+                HistoryEntry::Choice(description) => lines.extend(wrap(&format!("> {}", description), width)),
+                HistoryEntry::Check { summary, success } => {
+                    let colour = |line: String| if *success { line.green().to_string() } else { line.red().to_string() };
+                    lines.extend(wrap(summary, width).into_iter().map(colour));
+                }
+            }
+        }
+        draw(&lines, &self.sidebar());
+        prompt("Press Enter to return to the game. ")?;
+        Some(())
+    }
+
+    // The building from the top down, rooms the same number of moves from the start grouped together. Rooms an
+    // exit leads to from here are numbered, the current one is marked with @, and ones not yet visited are greyed out.
+    // Locked doors say why after the room's name.
+    fn map(&mut self) -> Option<()> {
+        let map = self.engine.map();
+        let mut lines = vec!["Map".bold().to_string()];
+        let mut destinations = vec![];
+        for row in (0..map.row_count()).rev() {
+            lines.push(String::new());
+            for room in map.rooms.iter().filter(|room| room.row == row) {
+                let marker = if room.current {
+                    "@".to_string()
+                } else if room.adjacent {
+                    destinations.push(room.location_id.clone());
+                    destinations.len().to_string()
+                } else {
+                    " ".to_string()
+                };
+                let name = if room.visited { room.name.as_str().stylize() } else { room.name.as_str().dark_grey() };
+                lines.push(format!("{:>2} {}", marker, name));
+                if let Some(reason) = &room.locked {
+                    let reason = wrap(&format!("Locked: {}", reason), main_width() - 3);
+                    lines.extend(reason.into_iter().map(|line| format!("   {}", line.red())));
+                }
+            }
+        }
+        lines.push(String::new());
+        lines.push(format!("Each move takes {}.", self.engine.content.rules.travel_time));
+        draw(&lines, &self.sidebar());
+
+        let answer = prompt("Room number to go to, or Enter to return: ")?;
+        if let Some(location_id) = answer.parse::<usize>().ok().and_then(|number| destinations.get(number.checked_sub(1)?)) {
+            let events = self.engine.travel(location_id);
+            self.handle_events(&events);
+        }
+        Some(())
+    }
+
+    fn manage_skills(&mut self) -> Option<()> {
+        loop {
+            let player = &self.engine.player;
+            let registry = &self.engine.content.skills;
+            let mut lines = vec![
+                "Skill Management".bold().to_string(),
+                format!("Available Skill Points: {}", player.skill_points),
+                String::new(),
+            ];
+            for (i, skill) in registry.skills.iter().enumerate() {
+                let attribute = registry.attribute(&skill.attribute).map_or(skill.attribute.as_str(), |attribute| attribute.name.as_str());
+                lines.push(format!("{:>2}. {}: {}: {}", i + 1, attribute.to_uppercase(), skill.name, player.skill(registry, &skill.id)));
+            }
+            draw(&lines, &self.sidebar());
+
+            let answer = prompt("Skill number to increase, or Enter to return: ")?;
+            let Ok(number) = answer.parse::<usize>() else { return Some(()) };
+            let registry = &self.engine.content.skills;
+            if let Some(skill) = number.checked_sub(1).and_then(|i| registry.skills.get(i)) {
+                self.engine.player.increase_skill(registry, &skill.id);
+            }
+        }
+    }
+
+    fn run(&mut self) -> Option<()> {
+        self.create_character()?;
+        loop {
+            self.draw_dialogue();
+            let line = prompt("> ")?;
+            let (command, argument) = line.split_once(' ').map_or((line.as_str(), ""), |(c, a)| (c, a.trim()));
+
+            match command {
+                "q" | "quit" => return Some(()),
+                "i" => self.inventory()?,
+                "k" => self.manage_skills()?,
+                "m" => self.map()?,
+                "h" => self.history(argument)?,
+                "e" => {
+                    let message = match self.engine.history.export(HISTORY_EXPORT_FILE) {
+                        Ok(()) => format!("Exported the history to {}.", HISTORY_EXPORT_FILE),
+                        Err(err) => format!("Export failed: {}", err),
+                    };
+                    self.messages.push(message);
+                }
+                "s" if !argument.is_empty() => {
+                    let message = match save::save(SAVE_DIR, argument, &SaveGame::from_engine(&self.engine)) {
+                        Ok(()) => format!("Saved to \"{}\".", argument),
+                        Err(err) => format!("Save failed: {}", err),
+                    };
+                    self.messages.push(message);
+                }
+                "l" if argument.is_empty() => {
+                    let slots = save::list_slots(SAVE_DIR);
+                    self.messages.push(if slots.is_empty() { "No saved games yet.".to_string() } else { slots.join(", ") });
+                }
+                "l" => {
+                    self.load(argument);
+                }
+                "d" if !argument.is_empty() => {
+                    let message = match save::delete(SAVE_DIR, argument) {
+                        Ok(()) => format!("Deleted \"{}\".", argument),
+                        Err(err) => format!("Delete failed: {}", err),
+                    };
+                    self.messages.push(message);
+                }
+                _ => {
+                    // Options are numbered as shown, skipping the ones that are hidden
+                    let option = command.parse::<usize>().ok().and_then(|number| {
+                        let view = self.engine.current_view()?;
+                        view.options.into_iter().nth(number.checked_sub(1)?)
+                    });
+                    match option {
+                        Some(OptionView { locked: Some(reason), .. }) => self.messages.push(format!("Locked: {}.", reason)),
+                        Some(option) => {
+                            let events = self.engine.choose(option.index);
+                            self.handle_events(&events);
+                        }
+                        None => self.messages.push(HELP.to_string()),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Tumbles the dice in place for a moment before they land, when there's a terminal to watch
+fn roll_animation(dice: (i32, i32)) {
+    if !io::stdout().is_terminal() {
+        return;
+    }
+    let mut stdout = io::stdout();
+    for frame in 0..8 {
+        let _ = write!(stdout, "\r  [{}] [{}]  ", frame % 6 + 1, (frame * 5 + 3) % 6 + 1);
+        let _ = stdout.flush();
+        thread::sleep(Duration::from_millis(70));
+    }
+    let _ = write!(stdout, "\r  [{}] [{}]  ", dice.0, dice.1);
+    let _ = stdout.flush();
+    thread::sleep(Duration::from_millis(400));
+}
+
+fn main_width() -> usize {
+    let columns = terminal::size().map_or(100, |(columns, _)| columns as usize);
+    columns.saturating_sub(SIDEBAR_WIDTH + 3).max(30)
+}
+
+// Clears the screen and prints the main column with the sidebar to its right
+fn draw(main: &[String], sidebar: &[String]) {
+    let width = main_width();
+    let mut stdout = io::stdout();
+    let _ = execute!(stdout, Clear(ClearType::All), MoveTo(0, 0));
+    for row in 0..main.len().max(sidebar.len()) {
+        let left = main.get(row).map_or("", String::as_str);
+        let right = sidebar.get(row).map_or("", String::as_str);
+        // Styled lines carry escape codes, so pad by what's visible
+        let padding = width.saturating_sub(visible_len(left));
+        println!("{}{} │ {}", left, " ".repeat(padding), right);
+    }
+    println!();
+}
+
+fn visible_len(line: &str) -> usize {
+    let mut len = 0;
+    let mut in_escape = false;
+    for c in line.chars() {
+        match (in_escape, c) {
+            (false, '\x1b') => in_escape = true,
+            (true, 'm') => in_escape = false,
+            (false, _) => len += 1,
+            _ => {}
+        }
+    }
+    len
+}
+
+// A scheduled event: the speaker's name in bold in front, or the narrator's text in italics
+fn announcement_lines(speaker: Option<&str>, text: &str, width: usize) -> Vec<String> {
+    let Some(speaker) = speaker else { return wrap(text, width).into_iter().map(|line| line.italic().to_string()).collect() };
+    let mut lines = wrap(&format!("{} {}", speaker, text), width);
+    if let Some(rest) = lines[0].strip_prefix(speaker) {
+        lines[0] = format!("{}{}", speaker.bold(), rest);
+    }
+    lines
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// Reads a trimmed line, or None when input has ended
+fn prompt(text: &str) -> Option<String> {
+    print!("{}", text);
+    io::stdout().flush().ok()?;
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().collect();
+    let seed = match take_seed_arg(&mut args) {
+        Ok(seed) => seed,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let content = match Content::load(DEFAULT_CONTENT_DIR) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Failed to load game content: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut tui = Tui::new(content);
+    // The same seed plays out the same rolls for the same choices
+    if let Some(seed) = seed {
+        tui.engine.set_dice_state(DiceState::Seeded { seed, rolls: 0 });
+    }
+    tui.run();
+    ExitCode::SUCCESS
+}
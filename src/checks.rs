@@ -0,0 +1,56 @@
+use std::fmt;
+
+// Named difficulties, easiest first. Targets in between take the next name up.
+pub const DIFFICULTIES: [(&str, i32); 9] = [
+    ("Trivial", 6),
+    ("Easy", 8),
+    ("Medium", 10),
+    ("Challenging", 12),
+    ("Formidable", 13),
+    ("Legendary", 14),
+    ("Heroic", 15),
+    ("Godly", 16),
+    ("Impossible", 18),
+];
+
+pub fn difficulty_name(target: i32) -> &'static str {
+    DIFFICULTIES
+        .iter()
+        .find(|(_, at_most)| target <= *at_most)
+        .map_or("Impossible", |(name, _)| name)
+}
+
+// What a challenge option shows before it's tried: "[Delusion – Challenging 12] 58%"
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckPreview {
+    pub skill: String,
+    pub skill_name: String, // What the skill registry calls it
+    pub target: i32,
+    pub chance: f64, // See `Rules::success_chance`
+}
+
+impl fmt::Display for CheckPreview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[{} – {} {}] {}%",
+            self.skill_name,
+            difficulty_name(self.target),
+            self.target,
+            (self.chance * 100.0).round()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previews_name_the_difficulty() {
+        let preview = CheckPreview { skill: "delusion".to_string(), skill_name: "Delusion".to_string(), target: 12, chance: 21.0 / 36.0 };
+        assert_eq!(preview.to_string(), "[Delusion – Challenging 12] 58%");
+        assert_eq!(difficulty_name(7), "Easy");
+        assert_eq!(difficulty_name(20), "Impossible");
+    }
+}
@@ -0,0 +1,481 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::Player;
+use crate::skills::SkillRegistry;
+use crate::time::{DayPhase, Time};
+use crate::world::WorldState;
+
+// When an option, dialogue or passive check applies, written as text in content files:
+//
+//   clock_open                      the flag is set
+//   item(Toy Plane)                 the player carries the item
+//   gizmo >= 4                      a skill, by ID, compared with a number
+//   visited(InspectClock)           a dialogue in this location has been entered, or visited(FirstFloor::Start)
+//   time >= 22:00                   the time of day, ignoring the day
+//   between(22:00, 06:00)           from the first time of day until the second, across midnight if need be
+//   phase(night)                    night, morning, afternoon or evening, see `DayPhase`
+//   day >= 2                        the day, counting from 1
+//   not a, a and b, a or b, (...)   also !, && and ||; not binds tightest, then and, then or
+//
+// Content that doesn't parse, or compares a skill skills.ron doesn't have, is rejected when it's loaded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Condition {
+    Flag(String),
+    Item(String),
+    Skill { skill: String, comparison: Comparison, value: i32 },
+    Visited { location: Option<String>, dialogue: String },
+    Time { comparison: Comparison, hour: i32, minute: i32 },
+    Between { from: (i32, i32), until: (i32, i32) }, // Hours and minutes; `until` itself is outside the window
+    Phase(DayPhase),
+    Day { comparison: Comparison, value: i32 },
+    Not(Box<Condition>),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+
+// Longest first, so ">=" isn't read as ">"
+const COMPARISONS: [(&str, Comparison); 6] = [
+    (">=", Comparison::GreaterOrEqual),
+    ("<=", Comparison::LessOrEqual),
+    ("==", Comparison::Equal),
+    ("!=", Comparison::NotEqual),
+    (">", Comparison::Greater),
+    ("<", Comparison::Less),
+];
+
+impl Comparison {
+    fn holds<T: Ord>(self, left: T, right: T) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = COMPARISONS.iter().find(|(_, comparison)| comparison == self).map_or("?", |(symbol, _)| symbol);
+        write!(f, "{}", symbol)
+    }
+}
+
+// What a condition is checked against
+pub struct Context<'a> {
+    pub player: &'a Player,
+    pub skills: &'a SkillRegistry,
+    pub world: &'a WorldState,
+    pub location_id: &'a str,
+    pub time: &'a Time,
+}
+
+impl Condition {
+    pub fn holds(&self, context: &Context) -> bool {
+        match self {
+            Condition::Flag(flag) => context.world.has_flag(flag),
+            Condition::Item(item) => context.player.items.contains(item),
+            Condition::Skill { skill, comparison, value } => comparison.holds(context.player.skill(context.skills, skill), *value),
+            Condition::Visited { location, dialogue } => {
+                context.world.has_visited(location.as_deref().unwrap_or(context.location_id), dialogue)
+            }
+            Condition::Time { comparison, hour, minute } => {
+                comparison.holds((context.time.hour, context.time.minute), (*hour, *minute))
+            }
+            Condition::Between { from, until } => {
+                let now = (context.time.hour, context.time.minute);
+                if from <= until {
+                    *from <= now && now < *until
+                } else {
+                    *from <= now || now < *until
+                }
+            }
+            Condition::Phase(phase) => context.time.phase() == *phase,
+            Condition::Day { comparison, value } => comparison.holds(context.time.day, *value),
+            Condition::Not(condition) => !condition.holds(context),
+            Condition::All(conditions) => conditions.iter().all(|condition| condition.holds(context)),
+            Condition::Any(conditions) => conditions.iter().any(|condition| condition.holds(context)),
+        }
+    }
+
+    // Every skill the condition compares, for checking them against the registry
+    pub fn skills(&self) -> Vec<&str> {
+        match self {
+            Condition::Skill { skill, .. } => vec![skill.as_str()],
+            Condition::Not(condition) => condition.skills(),
+            Condition::All(conditions) | Condition::Any(conditions) => conditions.iter().flat_map(Condition::skills).collect(),
+            _ => vec![],
+        }
+    }
+
+    // Every visited(...) in the condition as (location, dialogue), for checking the names exist
+    pub fn visits(&self) -> Vec<(Option<&str>, &str)> {
+        match self {
+            Condition::Visited { location, dialogue } => vec![(location.as_deref(), dialogue.as_str())],
+            Condition::Not(condition) => condition.visits(),
+            Condition::All(conditions) | Condition::Any(conditions) => conditions.iter().flat_map(Condition::visits).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Parentheses only where the precedence needs them, so conditions print the way writers type them
+        let grouped = |condition: &Condition, f: &mut fmt::Formatter, within_all: bool| match condition {
+            Condition::Any(_) => write!(f, "({})", condition),
+            Condition::All(_) if !within_all => write!(f, "({})", condition),
+            _ => write!(f, "{}", condition),
+        };
+        match self {
+            Condition::Flag(flag) => write!(f, "{}", flag),
+            Condition::Item(item) => write!(f, "item({})", item),
+            Condition::Skill { skill, comparison, value } => write!(f, "{} {} {}", skill, comparison, value),
+            Condition::Visited { location: Some(location), dialogue } => write!(f, "visited({}::{})", location, dialogue),
+            Condition::Visited { location: None, dialogue } => write!(f, "visited({})", dialogue),
+            Condition::Time { comparison, hour, minute } => write!(f, "time {} {:02}:{:02}", comparison, hour, minute),
+            Condition::Between { from, until } => {
+                write!(f, "between({:02}:{:02}, {:02}:{:02})", from.0, from.1, until.0, until.1)
+            }
+            Condition::Phase(phase) => write!(f, "phase({})", phase),
+            Condition::Day { comparison, value } => write!(f, "day {} {}", comparison, value),
+            Condition::Not(condition) => {
+                write!(f, "not ")?;
+                grouped(condition, f, false)
+            }
+            Condition::All(conditions) => {
+                for (i, condition) in conditions.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " and ")?;
+                    }
+                    grouped(condition, f, true)?;
+                }
+                Ok(())
+            }
+            Condition::Any(conditions) => {
+                for (i, condition) in conditions.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " or ")?;
+                    }
+                    write!(f, "{}", condition)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionError {
+    pub condition: String,
+    pub column: usize, // Counting from 1
+    pub message: String,
+}
+
+impl fmt::Display for ConditionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad condition \"{}\" at column {}: {}", self.condition, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ConditionError {}
+
+impl FromStr for Condition {
+    type Err = ConditionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { text, position: 0 };
+        let condition = parser.any()?;
+        parser.skip_spaces();
+        if parser.position < text.len() {
+            return Err(parser.error("expected \"and\", \"or\" or the end of the condition"));
+        }
+        Ok(condition)
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = ConditionError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Condition> for String {
+    fn from(condition: Condition) -> Self {
+        condition.to_string()
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize, // Byte offset into `text`
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn error(&self, message: &str) -> ConditionError {
+        ConditionError {
+            condition: self.text.to_string(),
+            column: self.text[..self.position].chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    // Takes `symbol` if it comes next
+    fn eat(&mut self, symbol: &str) -> bool {
+        self.skip_spaces();
+        if self.rest().starts_with(symbol) {
+            self.position += symbol.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    // Like `eat`, but a keyword has to be a whole word: "order" doesn't start with "or"
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_spaces();
+        let rest = self.rest();
+        let whole_word = rest.starts_with(keyword) && !rest[keyword.len()..].starts_with(is_name_char);
+        if whole_word {
+            self.position += keyword.len();
+        }
+        whole_word
+    }
+
+    fn name(&mut self) -> Option<&str> {
+        self.skip_spaces();
+        let rest = &self.text[self.position..];
+        let length = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        if length == 0 {
+            return None;
+        }
+        self.position += length;
+        Some(&rest[..length])
+    }
+
+    fn number(&mut self) -> Option<i32> {
+        self.skip_spaces();
+        let rest = &self.text[self.position..];
+        let sign = usize::from(rest.starts_with('-'));
+        let length = rest[sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - sign) + sign;
+        let number = rest[..length].parse().ok()?;
+        self.position += length;
+        Some(number)
+    }
+
+    fn any(&mut self) -> Result<Condition, ConditionError> {
+        let mut conditions = vec![self.all()?];
+        while self.eat_keyword("or") || self.eat("||") {
+            conditions.push(self.all()?);
+        }
+        Ok(if conditions.len() == 1 { conditions.remove(0) } else { Condition::Any(conditions) })
+    }
+
+    fn all(&mut self) -> Result<Condition, ConditionError> {
+        let mut conditions = vec![self.not()?];
+        while self.eat_keyword("and") || self.eat("&&") {
+            conditions.push(self.not()?);
+        }
+        Ok(if conditions.len() == 1 { conditions.remove(0) } else { Condition::All(conditions) })
+    }
+
+    fn not(&mut self) -> Result<Condition, ConditionError> {
+        if self.eat_keyword("not") || self.eat("!") {
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+        self.test()
+    }
+
+    fn test(&mut self) -> Result<Condition, ConditionError> {
+        if self.eat("(") {
+            let condition = self.any()?;
+            if !self.eat(")") {
+                return Err(self.error("expected \")\""));
+            }
+            return Ok(condition);
+        }
+
+        let start = self.position;
+        let Some(name) = self.name() else { return Err(self.error("expected a flag, item(...), visited(...) or a comparison")) };
+        let name = name.to_string();
+        if self.eat("(") {
+            let argument = self.argument()?;
+            return match name.as_str() {
+                "item" => Ok(Condition::Item(argument)),
+                "phase" => match DayPhase::ALL.into_iter().find(|phase| phase.name() == argument) {
+                    Some(phase) => Ok(Condition::Phase(phase)),
+                    None => {
+                        self.position = start;
+                        Err(self.error("expected phase(night), phase(morning), phase(afternoon) or phase(evening)"))
+                    }
+                },
+                "between" => {
+                    let window = argument.split_once(',').and_then(|(from, until)| Some((time_of_day(from)?, time_of_day(until)?)));
+                    match window {
+                        // An empty window would hide whatever it's on for good
+                        Some((from, until)) if from == until => {
+                            self.position = start;
+                            Err(self.error("between(...) needs two different times, or it's never true"))
+                        }
+                        Some((from, until)) => Ok(Condition::Between { from, until }),
+                        None => {
+                            self.position = start;
+                            Err(self.error("expected two times of day like between(22:00, 06:00)"))
+                        }
+                    }
+                }
+                "visited" => Ok(match argument.split_once("::") {
+                    Some((location, dialogue)) => {
+                        Condition::Visited { location: Some(location.to_string()), dialogue: dialogue.to_string() }
+                    }
+                    None => Condition::Visited { location: None, dialogue: argument },
+                }),
+                _ => {
+                    self.position = start;
+                    Err(self.error(&format!("unknown test \"{}\", expected item(...), visited(...), phase(...) or between(...)", name)))
+                }
+            };
+        }
+
+        self.skip_spaces();
+        let Some(&(symbol, comparison)) = COMPARISONS.iter().find(|(symbol, _)| self.rest().starts_with(symbol)) else {
+            return Ok(Condition::Flag(name));
+        };
+        self.position += symbol.len();
+
+        if name == "time" {
+            let (Some(hour), true, Some(minute)) = (self.number(), self.eat(":"), self.number()) else {
+                return Err(self.error("expected a time of day like 22:00"));
+            };
+            if !(0..24).contains(&hour) || !(0..60).contains(&minute) {
+                return Err(self.error("times of day go from 00:00 to 23:59"));
+            }
+            return Ok(Condition::Time { comparison, hour, minute });
+        }
+
+        let Some(value) = self.number() else { return Err(self.error("expected a number")) };
+        if name == "day" {
+            return Ok(Condition::Day { comparison, value });
+        }
+        Ok(Condition::Skill { skill: name, comparison, value })
+    }
+
+    // Everything up to the closing bracket, so item names can have spaces
+    fn argument(&mut self) -> Result<String, ConditionError> {
+        let Some(length) = self.rest().find(')') else { return Err(self.error("expected \")\"")) };
+        let argument = self.rest()[..length].trim().to_string();
+        if argument.is_empty() {
+            return Err(self.error("expected a name inside the brackets"));
+        }
+        self.position += length + 1;
+        Ok(argument)
+    }
+}
+
+// "06:00", with the hour and minute in range
+fn time_of_day(text: &str) -> Option<(i32, i32)> {
+    let (hour, minute) = text.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse().ok()?, minute.parse().ok()?);
+    ((0..24).contains(&hour) && (0..60).contains(&minute)).then_some((hour, minute))
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Condition {
+        text.parse().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    #[test]
+    fn conditions_test_the_game_state() {
+        let skills = SkillRegistry::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap();
+        let player = Player {
+            attributes: [("underworld".to_string(), 3)].into(),
+            skills: [("gizmo".to_string(), 1)].into(),
+            items: vec!["Toy Plane".to_string()],
+            ..Player::default()
+        };
+        let mut world = WorldState::default();
+        world.set_flag("clock_open");
+        world.mark_visited("FirstFloor", "Start");
+        let time = Time { day: 2, hour: 22, minute: 15 };
+        let context = Context { player: &player, skills: &skills, world: &world, location_id: "Vestibule", time: &time };
+        let holds = |text: &str| parse(text).holds(&context);
+
+        assert!(holds("clock_open"));
+        assert!(!holds("wound"));
+        assert!(holds("item(Toy Plane) and gizmo >= 4"));
+        assert!(!holds("gizmo > 4"));
+        assert!(holds("visited(FirstFloor::Start) && !visited(Start)"));
+        assert!(holds("time >= 22:00 or time < 06:00"));
+        assert!(!holds("not (clock_open or wound)"));
+        assert!(holds("wound or clock_open and civic_engineering == 1"));
+        assert!(holds("between(22:00, 06:00) and phase(evening) and day == 2"));
+        assert!(!holds("between(06:00, 22:15) or phase(night) or day > 2"));
+    }
+
+    #[test]
+    fn conditions_print_the_way_they_parse() {
+        for text in [
+            "clock_open",
+            "item(Toy Plane) and not visited(FirstFloor::Start)",
+            "(wound or oldtime_religion < 3) and time != 05:37",
+            "not (a and b) or c",
+            "between(22:00, 06:00) and not phase(morning) or day >= 3",
+        ] {
+            assert_eq!(parse(text).to_string(), text);
+        }
+        assert_eq!(parse("!wound&&(a||b)").to_string(), "not wound and (a or b)");
+    }
+
+    #[test]
+    fn malformed_conditions_say_where() {
+        let error = |text: &str| text.parse::<Condition>().unwrap_err();
+        assert_eq!(error("wound and").column, 10);
+        assert_eq!(error("gizmo >= four").message, "expected a number");
+        assert_eq!(error("time < 25:00").message, "times of day go from 00:00 to 23:59");
+        assert_eq!(error("(wound").message, "expected \")\"");
+        assert_eq!(error("phase(teatime)").column, 1);
+        assert_eq!(error("between(22:00)").message, "expected two times of day like between(22:00, 06:00)");
+        assert_eq!(error("wound and between(22:00, 22:00)").column, 11);
+        assert_eq!(error("between(22:00, 22:00)").message, "between(...) needs two different times, or it's never true");
+        assert_eq!(error("wound clock_open").column, 7);
+        assert!(error("smell(roses)").to_string().starts_with("bad condition \"smell(roses)\" at column 1"));
+
+        // Content with a bad condition doesn't load
+        let option = ron::from_str::<crate::dialogues::DialogueOption>(r#"DialogueOption(visible_when: Some("gizmo >> 4"))"#);
+        assert!(option.err().unwrap().to_string().contains("bad condition \"gizmo >> 4\" at column 8"));
+    }
+}
@@ -1,56 +1,219 @@
 use std::collections::HashMap;
-
-#[derive(Clone)]
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ron::extensions::Extensions;
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::condition::Condition;
+use crate::rules::Rules;
+use crate::schedule::Schedule;
+use crate::skills::SkillRegistry;
+use crate::time::Duration;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Location {
     pub name: String,
+    #[serde(serialize_with = "start_first")]
     pub dialogues: HashMap<String, Dialogue>,
-    pub exits: Vec<String>, // Names of other locations you can move to
+    #[serde(default)]
+    pub exits: Vec<Exit>, // Doors to other locations, for travelling on the map
+}
+
+// A door to another location. Content can write just the location's ID, "FirstFloor", or lock the door:
+//
+//   Exit(to: "AdministratorRoom", requires: "item(Administrator's letter)", locked_message: "It's locked.")
+//
+// `locked_dialogue` is where trying the locked door takes the player, read like an option's target.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ExitFile", into = "ExitFile")]
+pub struct Exit {
+    pub to: String,
+    pub requires: Option<Condition>,     // The door is locked while this doesn't hold
+    pub locked_message: Option<String>,  // Why, as the map shows it; the condition itself if left out
+    pub locked_dialogue: Option<String>,
+}
+
+// How exits are written: a bare ID when there's no lock, so most content stays a list of names
+#[derive(Serialize, Deserialize)]
+#[serde(untagged, rename = "Exit")]
+enum ExitFile {
+    To(String),
+    Locked {
+        to: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        requires: Option<Condition>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        locked_message: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        locked_dialogue: Option<String>,
+    },
+}
+
+impl From<ExitFile> for Exit {
+    fn from(file: ExitFile) -> Self {
+        match file {
+            ExitFile::To(to) => Exit::new(to),
+            ExitFile::Locked { to, requires, locked_message, locked_dialogue } => Exit { to, requires, locked_message, locked_dialogue },
+        }
+    }
+}
+
+impl From<Exit> for ExitFile {
+    fn from(exit: Exit) -> Self {
+        match exit {
+            Exit { to, requires: None, locked_message: None, locked_dialogue: None } => ExitFile::To(to),
+            Exit { to, requires, locked_message, locked_dialogue } => ExitFile::Locked { to, requires, locked_message, locked_dialogue },
+        }
+    }
 }
 
-#[derive(Clone)]
+impl Exit {
+    pub fn new(to: String) -> Self {
+        Self { to, requires: None, locked_message: None, locked_dialogue: None }
+    }
+
+    // What the map says about the door while it's locked
+    pub fn lock_reason(&self) -> String {
+        match (&self.locked_message, &self.requires) {
+            (Some(message), _) => message.clone(),
+            (None, Some(condition)) => format!("needs {}", condition),
+            (None, None) => "locked".to_string(),
+        }
+    }
+}
+
+// Fields left out of a content file fall back to the Default impls below,
+// the same way `..Default::default()` works in Rust.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DialogueOption {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>, // Stable ID for saves; without one the option is known by its position
     pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub challenge_attribute: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub challenge_number: Option<i32>,
+    #[serde(skip_serializing_if = "CheckKind::is_white")]
+    pub check_kind: CheckKind,
     pub success_dialogue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub failure_dialogue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub item_to_pickup: Option<String>,
-    pub visible_when: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible_when: Option<Condition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub flags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<Effect>, // Applied in order once the option is taken, after any roll
+}
+
+// Something an option does when taken, or a dialogue does each time it's entered
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    AddItem(String),
+    RemoveItem(String),
+    SetFlag(String),
+    ClearFlag(String),
+    GrantXp(i32),
+    // Wears off after `duration` of game time, which can't be 0. Content written before durations calls it `minutes`.
+    ModifySkill {
+        skill: String,
+        amount: i32,
+        #[serde(alias = "minutes")]
+        duration: Duration,
+        source: String,
+    },
+    AdvanceTime(Duration),
+    MoveTo(String), // A location, or "Location::Node"; the player goes there once the other effects are done
+}
 
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Effect::AddItem(item) => write!(f, "+item: {}", item),
+            Effect::RemoveItem(item) => write!(f, "-item: {}", item),
+            Effect::SetFlag(flag) => write!(f, "+flag: {}", flag),
+            Effect::ClearFlag(flag) => write!(f, "-flag: {}", flag),
+            Effect::GrantXp(xp) => write!(f, "+{} XP", xp),
+            Effect::ModifySkill { skill, amount, duration, source } => {
+                write!(f, "{} {:+} for {} ({})", skill, amount, duration, source)
+            }
+            Effect::AdvanceTime(duration) => write!(f, "+{}", duration),
+            Effect::MoveTo(location) => write!(f, "move to {}", location),
+        }
+    }
+}
+
+// White checks can be tried again once the skill has gone up since failing them.
+// Red checks get a single attempt, pass or fail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckKind {
+    #[default]
+    White,
+    Red,
 }
 
-#[derive(Clone)]
+impl CheckKind {
+    fn is_white(&self) -> bool {
+        *self == CheckKind::White
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Dialogue {
     pub speaker: String,
     pub intro: String,
     pub options: Vec<DialogueOption>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub passive_check: Vec<PassiveCheck>, // New field for passive dialogue checks
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub xp_reward: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible_when: Option<Condition>, // Options leading here are hidden while this doesn't hold
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<Effect>, // Applied every time the dialogue is entered
     pub is_hidden: bool,
-    pub time: Option<i32>,
+    pub time: Option<Duration>, // How long entering the dialogue takes
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PassiveCheck {
     pub skill: String,          // The player's skill to check
     pub target: i32,            // The number to check against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub success_text: Option<String>, // Text to display on success (Optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub failure_text: Option<String>, // Text to display on failure (Optional)
-    pub speaker: Option<String>, // The speaker, who will be the same in both success and failure cases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>, // Who says it in both cases; the skill's name if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible_when: Option<Condition>, // The check is skipped entirely while this doesn't hold
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub repeatable: bool, // Speaks up on every visit, rather than only the first time it has something to say
 }
 
 impl Default for DialogueOption {
     fn default() -> Self {
         DialogueOption {
+            id: None,
             description: "Continue".to_string(),
             challenge_attribute: None,
             challenge_number: None,
+            check_kind: CheckKind::White,
             success_dialogue: Some("Start".to_string()),
             failure_dialogue: None,
             item_to_pickup: None,
             visible_when: None,
             flags: None,
+            effects: vec![],
         }
     }
 }
@@ -65,12 +228,48 @@ impl Default for Dialogue {
             ],
             passive_check: vec![],
             xp_reward: None,
+            visible_when: None,
+            effects: vec![],
             is_hidden: true,
-            time: Some(1),
+            time: Some(Duration::from_minutes(1)),
         }
     }
 }
 
+impl Dialogue {
+    // Every skill the dialogue mentions, with where it does: ("option 2 challenge", "gizmo")
+    pub fn skill_references(&self) -> Vec<(String, &str)> {
+        let mut references = vec![];
+        let mut scopes: Vec<(String, &Option<Condition>, &[Effect])> = vec![("dialogue".to_string(), &self.visible_when, &self.effects)];
+        for (i, option) in self.options.iter().enumerate() {
+            scopes.push((format!("option {}", i + 1), &option.visible_when, &option.effects));
+        }
+        for (i, check) in self.passive_check.iter().enumerate() {
+            scopes.push((format!("passive check {}", i + 1), &check.visible_when, &[]));
+        }
+        for (place, condition, effects) in scopes {
+            for skill in condition.iter().flat_map(Condition::skills) {
+                references.push((format!("{} condition", place), skill));
+            }
+            for effect in effects {
+                if let Effect::ModifySkill { skill, .. } = effect {
+                    references.push((format!("{} effect", place), skill.as_str()));
+                }
+            }
+        }
+
+        for (i, option) in self.options.iter().enumerate() {
+            if let Some(skill) = &option.challenge_attribute {
+                references.push((format!("option {} challenge", i + 1), skill.as_str()));
+            }
+        }
+        for (i, check) in self.passive_check.iter().enumerate() {
+            references.push((format!("passive check {}", i + 1), check.skill.as_str()));
+        }
+        references
+    }
+}
+
 impl Location {
     pub fn new(name: String) -> Self {
         Self {
@@ -85,1468 +284,285 @@ impl Location {
     }
 
     pub fn add_exit(&mut self, exit: String) {
-        self.exits.push(exit);
+        self.exits.push(Exit::new(exit));
     }
 }
 
 
-//create defaults and use them, reduce space taken up
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-pub fn create_locations() -> HashMap<String, Location> {
-    let mut locations = HashMap::new();
-
-    // Define sample dialogues for the Vestibule
-    let mut vestibule_dialogues = HashMap::new();
-    vestibule_dialogues.insert(
-        "Start".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "The front door swings shut, cutting off the bitter wind like a scythe. You stand in the harsh light of a public apartment vestibule. A grid of mailboxes wait, closed, and a grandfather clock stands stout against the wall, like an elderly servant whose crooked back can't quite stand up to attention.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Inspect the grandfather clock.".to_string(),
-                    success_dialogue: Some("InspectClock".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Look in the mailboxes.".to_string(),
-                    success_dialogue: Some("VestibuleMailboxes".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Go to the first floor.".to_string(),
-                    success_dialogue: Some("FirstFloor".to_string()),
-                    ..Default::default()
-                },
-            ],
-            is_hidden: false,
-            ..Default::default()
-        },
-    );
-
-    vestibule_dialogues.insert(
-        "InspectClock".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "A round, pale face crossed by dark lines stares down at you. It has seen much, and forgotten more.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "What stories could you tell me, old man? (Delusion 12)".to_string(), 
-                    challenge_attribute: Some("delusion".to_string()),
-                    challenge_number: Some(12),
-                    success_dialogue: Some("HungryClock".to_string()), 
-                    failure_dialogue: Some("MockingClock".to_string()),
-                    ..Default::default()
-
-                    // hungry for the gear? it wants to eat its beating heart. Can give you a hint of where to find it
-                },
-
-                //to go back to the room's origin, the code is 'Start'
-
-                DialogueOption {
-                    description: "Check the time.".to_string(), 
-                    success_dialogue: Some("BrokenClock".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Goodbye, fair clock. (End conversation).".to_string(), 
-                    success_dialogue: Some("Start".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            passive_check: vec![
-                PassiveCheck {
-                    skill: "robot".to_string(),
-                    target: 10,
-                    success_text: Some("You know its kind. The unrelenting metronome to which you dance.".to_string()),
-                    failure_text: None,
-                    speaker: Some("Robot".to_string())
-                },
-                PassiveCheck {
-                    skill: "gizmo".to_string(),
-                    target: 12,
-                    success_text: Some("It's in bad shape, boss. The varnish is falling off, the face needs a solid wipe down, 
-                    and I don't see a notice of last maintenance *anywhere*.".to_string()),
-                    failure_text: None,
-                    speaker: Some("Gizmo".to_string())
-                },
-            ],
-            ..Default::default()
-        },
-    );
-
-    vestibule_dialogues.insert(
-        "HungryClock".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "BrokenClock".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"Its two hands, the shorter ending in a stylized sun and the longer in a crescent moon, are stuck at 5 hours and 37 minutes".to_string(), // this is a CLUE
-            options: vec![
-                DialogueOption {
-                    description: "What a waste. Surely I could fix it up?".to_string(),
-                    success_dialogue: Some("ClockRecommends".to_string()), //gizmo to fix it, or telling you to open it up to do so
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Five-thirty-seven in the morning or night?".to_string(),
-                    success_dialogue: Some("ClockFutile".to_string()), //futilely ask if this is morning or night
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Stand proud, fallen soldier of the Republic! (Salute the clock)".to_string(),
-                    success_dialogue: Some("ClockSalute".to_string()), //salute it
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Goodbye, mysterious clock.".to_string(),
-                    success_dialogue: Some("Start".to_string()), //exit
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-
-    // have a passive apparatchik check to know about fictional soviet-era clock and time policies
-
-    // this check to notice that it's missing a crucial gear, and what to look for elsewhere
-    // can be retried later if you happen to find the gear with a bonus, if you succeed here you can
-    //fix it automatically with the gear
-
-    vestibule_dialogues.insert(
-        "ClockInterior".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "The oak casing is stiff, the hinges squeaky from disuse. It takes a solid heave with both hands to pull it out, 
-            and the cover nearly comes off in your grasp. Inside, you see a forest of gears, exposed to the eye and glimmering 
-            under a thin coat of dust. On the bottom, resting against the casing, lies a tin model airplane.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "What kind of plane is it? (Rocketry 6)".to_string(),
-                    challenge_attribute: Some("rocketry".to_string()),
-                    challenge_number: Some(6),
-                    success_dialogue: Some("ClockPlane".to_string()),
-                    failure_dialogue: Some("ClockPlaneFail".to_string()),
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Let's fix you up, soldier. (Gizmo 12)".to_string(),
-                    challenge_attribute: Some("gizmo".to_string()),
-                    challenge_number: Some(12),
-                    success_dialogue: Some("ClockMissingGear".to_string()),
-                    failure_dialogue: Some("ClockFixImpossible".to_string()),
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Close the casing.".to_string(),
-                    success_dialogue: Some("InspectClock".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "MockingClock".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"It seems to talk to you. It says 'Why are you talking to a clock, you absolute dolt? You twat. Has my non-sentient existence come to the point that madmen come in off the street and try to engage me in conversation?'".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Okay, that's just, like, your opinion, man. (End conversation)".to_string(),
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Tell me your secrets!".to_string(),
-                    success_dialogue: Some("ClockDecline".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockDecline".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "No.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Well, alright then.".to_string(),
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Fuck you, and your little cuckoo too.".to_string(),
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockRecommends".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"You'd have to get into this thing's guts. Are you mentally and *spiritually* prepared to mess around with this poor, fallen soldier's insides? On the off chance you can restore life to the dead?".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "On second thought, let's not do that.".to_string(),
-                    success_dialogue: Some("BrokenClock".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Come on, why did you have to phrase it that way?".to_string(),
-                    success_dialogue: Some("ClockPhrasing".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Let's do this.".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockPhrasing".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "Like what?".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Like I'm doing something creepy here! It's just a broken clock.".to_string(),
-                    success_dialogue: Some("JustABrokenClock".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Like this is some *religious* thing. That's not what I'm about.".to_string(),
-                    success_dialogue: Some("ReligiousClock".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Like... you know.".to_string(),
-                    success_dialogue: Some("ClockSuggestive".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "You know what, never mind.".to_string(),
-                    success_dialogue: Some("BrokenClock".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Fine, I'll get *into its guts*. What's in there?".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "JustABrokenClock".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "*Just* a broken clock? Shows what you know. This right here is a veteran of public administration, an honored servant of the people, languishing under the failures of the regime. How dare you ignore his deeds and his suffering.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Well, Grandfather Clockovitch here can languish a little longer.".to_string(),
-                    success_dialogue: Some("BrokenClock".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Fine, I'll look inside. As long as there's no more creepy phrasing.".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ReligiousClock".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "Religious? What are you talking about? Grandfather Clockovitch is a ".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            //grandfather clockovitch is a secret religionist! asks that you forgive him anyway. Will you hold back your generosity from this sinne- I mean, reactionary?
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockSuggestive".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "I'm very, *very* sure I don't.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Like it's something... sexual.".to_string(),
-                    success_dialogue: Some("ClockSexual".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Fine, I'll leave off. What was that about a sun and moon again?".to_string(),
-                    success_dialogue: Some("BrokenClock".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockSexual".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"Wow. Just wow. Just what about sticking your hands *wrist deep* in your comrade's bloody, pulsating wound seems *sexual* to you?".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: r"It's not you, it's everyone. Come up with a theoretical justification for your objectively weird thoughts. ".to_string(),
-                    challenge_attribute: Some("pathology".to_string()),
-                    challenge_number: Some(8),
-                    success_dialogue: Some("ClockPerversion".to_string()), //Pathology check to justify yourself
-                    failure_dialogue: Some("ClockTrauma".to_string()),
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "There it is again! You're putting this weird *emphasis* on things.".to_string(),
-                    success_dialogue: Some("ClockEmphasis".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Actually, you know what? This isn't a sexual thing, it's a religious thing.".to_string(),
-                    success_dialogue: Some("ClockSexualReligion".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockPerversion".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"It's an extremely common and not at all shameful perversion, characteristic of the Republic's struggles against the insidious American *Central Intelligence Agency*.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Let's go with that.".to_string(),
-                    success_dialogue: Some("ClockCIA".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "No, you can't possibly be serious. How can that possibly make sense?".to_string(),
-                    success_dialogue: Some("ClockExplanation".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockTrauma".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "It's a trauma response.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "What, seriously?".to_string(),
-                    success_dialogue: Some("ClockNotTrauma".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "Did I have to do... that... during The War? Do I have repressed memories I need to process?".to_string(),
-                    success_dialogue: Some("ClockRepression".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockNotTrauma".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"Look, all I now is that whenever something like this happens to you, there's exactly one phrase bouncing around this empty skull, and it's 'trauma response'. Trauma response this, trauma response that. It gets you out of anything.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockRepression".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"Something here is definitely repressed, but it's not memories. You're no amnesiac, as much as you might like to be, at times.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockCIA".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: "No need for an explanation, seeing as we're all on the same page.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockExplanation".to_string(),
-        Dialogue {
-            speaker: "Grandfather Clock".to_string(),
-            intro: r"Simple. Communism is built upon love for your comrade. \
-            Not bourgeous romantic love, or feudal familial love, or reactionary \
-            love of country, but the superior and pure love of the worker standing \
-            by your side. The CIA's modus operandi is to undermine the foundation \
-            of communism by turning comrades against one another. This objective, \
-            to our great misfortune, has been largely successful. Everywhere now, \
-            you can see signs of bourgeois romantic love displacing \
-            true communist camaraderie, and bringing with it a perverse focus on \
-            sex. But this train of thought, by re-associating the apparently \
-            sexual elements with the suffering of one's comrades, reinforces the \
-            revolutionary spirit and wards off capitalist infiltration. The only \
-            reason you recoil from this image, instead of embracing it, is \
-            because you yet possess a remnant of the bourgeois ego, nurtured by CIA \
-            propaganda, to which laying down all and destroying all boundaries \
-            for the sake of your comrades is unacceptable.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "You know, somehow that fails to make me okay with all of this.".to_string(),
-                    success_dialogue: Some("".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()), 
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "If this is what communism has come to, shave my mustache and call me Milton Friedman. (Leave in disgust)".to_string(),
-                    success_dialogue: Some("Start".to_string()), 
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockMissingGear".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            xp_reward: Some(10),
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockFixImpossible".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "ClockPlane".to_string(),
-        Dialogue {
-            speaker: "SU-25 Grach".to_string(),
-            intro: "This is the Sukhoi SU-25 Grach, a single-seat twin-engine close air support jet, most notable for its extensive counter-insurgency missions in Afghanistan.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Pick it up".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), 
-                    item_to_pickup: Some("Sukhoi SU-25 Grach model".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "It's only a model (Return)".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            xp_reward: Some(5),
-            ..Default::default()
-        }
-    );
-
-    // add it's only a model passive dialogue
-
-    vestibule_dialogues.insert(
-        "ClockPlaneFail".to_string(),
-        Dialogue {
-            speaker: "Toy Plane".to_string(),
-            intro: "It's just a toy plane. Looks cool though.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Pick it up".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), // Go back to the room's main dialogue
-                    item_to_pickup: Some("Toy Plane".to_string()),
-                    ..Default::default()
-                },
-
-                DialogueOption {
-                    description: "(Return)".to_string(),
-                    success_dialogue: Some("ClockInterior".to_string()), // Go back to the room's main dialogue
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "VestibuleMailboxes".to_string(),
-        Dialogue{
-            speaker: "Mailboxes".to_string(),
-            intro: "Four rows of square, wooden mailboxes sit above a dusty bar mounted to the wall.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Examine the mailboxes".to_string(),
-                    success_dialogue: Some("MailboxExamination".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Look at the first row".to_string(),
-                    success_dialogue: Some("MailboxFirstRow".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Look at the second row".to_string(),
-                    success_dialogue: Some("MailboxSecondRow".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Look at the third row".to_string(),
-                    success_dialogue: Some("MailboxThirdRow".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Look at the fourth row".to_string(),
-                    success_dialogue: Some("MailboxFourthRow".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Exit".to_string(),
-                    success_dialogue: Some("Start".to_string()),
-                    ..Default::default()
-                },
-            ],
-            passive_check: vec![
-                PassiveCheck {
-                    skill: "dossier".to_string(),
-                    target: 8,
-                    success_text: Some("The first mailbox in the fourth row, number 400, just reads 'Administrator.' This one's yours.".to_string()),
-                    failure_text: None,
-                    speaker: Some("Dossier".to_string())
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "MailboxFirstRow".to_string(),
-        Dialogue {
-            speaker: "Mailboxes".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Open 106: Kutuzov".to_string(),
-                    success_dialogue: Some("Mailbox106".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 108: ".to_string(),
-                    success_dialogue: Some("Mailbox108".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Return".to_string(),
-                    success_dialogue: Some("VestibuleMailboxes".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "MailboxSecondRow".to_string(),
-        Dialogue {
-            speaker: "Mailboxes".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Open 201: ".to_string(),
-                    success_dialogue: Some("Mailbox201".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 205: ".to_string(),
-                    success_dialogue: Some("Mailbox205".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 206: ".to_string(),
-                    success_dialogue: Some("Mailbox206".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 208: ".to_string(),
-                    success_dialogue: Some("Mailbox208".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Return".to_string(),
-                    success_dialogue: Some("VestibuleMailboxes".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "MailboxThirdRow".to_string(),
-        Dialogue {
-            speaker: "Mailboxes".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Open 301: ".to_string(),
-                    success_dialogue: Some("Mailbox301".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 303: ".to_string(),
-                    success_dialogue: Some("Mailbox303".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 304: ".to_string(),
-                    success_dialogue: Some("Mailbox304".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 305: ".to_string(),
-                    success_dialogue: Some("Mailbox305".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Open 307: ".to_string(),
-                    success_dialogue: Some("Mailbox307".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Return".to_string(),
-                    success_dialogue: Some("VestibuleMailboxes".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "MailboxFourthRow".to_string(),
-        Dialogue {
-            speaker: "Mailboxes".to_string(),
-            intro: "The lone nameplate reads: Administrator. It's clean, free of dust, and undamaged.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Open 400: Administrator".to_string(),
-                    success_dialogue: Some("Mailbox400".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Return".to_string(),
-                    success_dialogue: Some("VestibuleMailboxes".to_string()),
-                    ..Default::default()
-                },
+// Keeps content files stable between saves: "Start" leads, the rest follow alphabetically
+fn start_first<S: Serializer>(dialogues: &HashMap<String, Dialogue>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut ids: Vec<&String> = dialogues.keys().collect();
+    ids.sort_by_key(|id| (id.as_str() != "Start", id.as_str()));
+    serializer.collect_map(ids.into_iter().map(|id| (id, &dialogues[id])))
+}
 
-            ],
-            ..Default::default()
-        }
-    );
+pub const DEFAULT_CONTENT_DIR: &str = "content";
 
-    vestibule_dialogues.insert(
-        "MailboxExamination".to_string(),
-        Dialogue{
-            speaker: "Mailboxes".to_string(),
-            intro: "Most have no nameplate at all. Maybe a dozen or so apartments are actually assigned. Most of those are empty too.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                },
-            ],
-            passive_check: vec![
-                PassiveCheck {
-                    skill: "robot".to_string(),
-                    target: 0,
-                    success_text: Some("Good. The people here are dutiful. They collect their mail on time.".to_string()),
-                    failure_text: None,
-                    speaker: Some("Robot".to_string())
-                },
+// Where the player starts in a location that's entered by name
+pub const ENTRY_DIALOGUE: &str = "Start";
 
-                PassiveCheck {
-                    skill: "delusion".to_string(),
-                    target: 10,
-                    success_text: Some("Unlikely. These mailboxes are sad. Lonely. Touch-starved.".to_string()),
-                    failure_text: None,
-                    speaker: Some("Delusion".to_string())
-                },
-            ],
-            ..Default::default()
-        }
-    );
+// A move's target: "FirstFloor" is that location's entry dialogue, "FirstFloor::ButlerResponse" a particular one
+pub fn location_target(target: &str) -> (String, String) {
+    match target.split_once("::") {
+        Some((location_id, dialogue_id)) => (location_id.to_string(), dialogue_id.to_string()),
+        None => (target.to_string(), ENTRY_DIALOGUE.to_string()),
+    }
+}
 
-    vestibule_dialogues.insert(
-        "Mailbox106".to_string(),
-        Dialogue{
-            speaker: "Mailboxes".to_string(),
-            intro: "This mailbox's cover is hanging off its hinge. The nameplate, Kutuzov, is slightly bent.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("Mailbox106Part2".to_string()),
-                    ..Default::default()
-                },
-            ],
-            passive_check: vec![
-                PassiveCheck {
-                    skill: "gunsmoke".to_string(),
-                    target: 10,
-                    success_text: Some("Psh, what a pussy. A real man could knock the cover clean off. Establish dominance. Punch his mailbox *harder*.".to_string()),
-                    failure_text: Some("Oh yeah, this Kutuzov fellow is a four-star badass. It takes a real man to punch his own mailbox.".to_string()),
-                    speaker: Some("Gunsmoke".to_string()),
-                },
-                PassiveCheck {
-                    skill: "pathology".to_string(),
-                    target: 10,
-                    success_text: Some("This 'Kutuzov' suffers from a condition in which he gives human qualities to inanimate objects. I'm sure you can't relate".to_string()),
-                    failure_text: Some("It takes a real man to punch *someone else's* mailbox. Punching your own is just pathetic.".to_string()),
-                    speaker: Some("Pathology".to_string()),
-                },
-                PassiveCheck {
-                    skill: "transcendence".to_string(),
-                    target: 10,
-                    success_text: Some("This is *not* the New Soviet Man. Or Woman.".to_string()),
-                    failure_text: Some("Why are we assuming Kutuzov is a man? Women can annihilate state property too.".to_string()),
-                    speaker: Some("Transcendence".to_string()),
-                },
-            ],
-            ..Default::default()
-        }
-    );
+// An option's target, from a dialogue in `location_id`. A dialogue there wins over a location of the same
+// name; anything else is taken as a location, and a target that names neither stays here so it can be reported.
+pub fn resolve_target(locations: &HashMap<String, Location>, location_id: &str, target: &str) -> (String, String) {
+    let here = locations.get(location_id).is_some_and(|location| location.dialogues.contains_key(target));
+    if here || (!target.contains("::") && !locations.contains_key(target)) {
+        (location_id.to_string(), target.to_string())
+    } else {
+        location_target(target)
+    }
+}
 
-    vestibule_dialogues.insert(
-        "Mailbox106Part2".to_string(),
-        Dialogue{
-            speaker: "Mailboxes".to_string(),
-            intro: "Ahem. As I was saying, the box is stuffed with official notices and bills. One of them sticks out: an empty, ripped-open court notice.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Punch it *harder*.".to_string(),
-                    //gunsmoke check, failure you lose health, break your wrist
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                }, // this option should become available if the gunsmoke 
-                // or should it?
-                DialogueOption {
-                    description: "I'm not doing that.".to_string(),
-                    success_dialogue: Some("MailboxFirstRow".to_string()),
-                    ..Default::default()
-                },
-            ],
-            passive_check: vec![
-                PassiveCheck {
-                    skill: "dossier".to_string(),
-                    target: 10,
-                    success_text: Some("These envelopes carry three kinds of notices: Fines, Summons, and Divorces. Take your bets.".to_string()),
-                    failure_text: None,
-                    speaker: Some("Dossier".to_string()),
-                },   
-            ],
-            ..Default::default()
-        }
-    );
+// The game's read-only content. What a playthrough changes lives in `world::WorldState`.
+#[derive(Clone, Default)]
+pub struct Content {
+    pub locations: HashMap<String, Location>,
+    pub skills: SkillRegistry,
+    pub rules: Rules,
+    pub schedule: Schedule,
+}
 
-    //kutuzov is loud and violent, nobody likes him, 108 remains only because they can't leave or somehow don't midn
-    //106 is beaten up, slammed, punched
+impl Content {
+    // Fails on the first skill the locations use that skills.ron doesn't define, or skill modifier that lasts no time
+    pub fn load(content_dir: impl AsRef<Path>) -> Result<Self, ContentError> {
+        let content_dir = content_dir.as_ref();
+        let skills = SkillRegistry::load(content_dir)?;
+        let rules = Rules::load(content_dir)?;
+        let locations = load_locations(content_dir)?;
+
+        let mut location_ids: Vec<&String> = locations.keys().collect();
+        location_ids.sort();
+        for location_id in location_ids {
+            let path = || content_dir.join("locations").join(format!("{}.ron", location_id));
+            for exit in &locations[location_id].exits {
+                if let Some(skill) = exit.requires.iter().flat_map(|condition| condition.skills()).find(|skill| !skills.contains(skill)) {
+                    let message = format!("exit to {} uses unknown skill \"{}\"", exit.to, skill);
+                    return Err(ContentError::Invalid { path: path(), message });
+                }
+            }
+            let mut dialogues: Vec<_> = locations[location_id].dialogues.iter().collect();
+            dialogues.sort_by_key(|(id, _)| id.as_str());
+            for (dialogue_id, dialogue) in dialogues {
+                if let Some((place, skill)) = dialogue.skill_references().into_iter().find(|(_, skill)| !skills.contains(skill)) {
+                    return Err(ContentError::Invalid {
+                        path: path(),
+                        message: format!("{}: {} uses unknown skill \"{}\"", dialogue_id, place, skill),
+                    });
+                }
+                // It would wear off before it ever counted
+                let mut effects = dialogue.effects.iter().chain(dialogue.options.iter().flat_map(|option| &option.effects));
+                if effects.any(|effect| matches!(effect, Effect::ModifySkill { duration, .. } if duration.is_zero())) {
+                    let message = format!("{}: a skill modifier lasts 0m", dialogue_id);
+                    return Err(ContentError::Invalid { path: path(), message });
+                }
+            }
+        }
+        let schedule = Schedule::load(content_dir, &locations, &skills)?;
+        Ok(Self { locations, skills, rules, schedule })
+    }
 
-    vestibule_dialogues.insert(
-        "Mailbox400".to_string(),
-        Dialogue{
-            speaker: "Mailboxes".to_string(),
-            intro: "There's just one envelope, crisp and clean, lying on the bottom. The writing on the front reads 'Apologies'.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Whose writing?. (Dossier 10)".to_string(),
-                    challenge_attribute: Some("dossier".to_string()),
-                    challenge_number: Some(10),
-                    success_dialogue: Some("Mailbox400LetterSuccess".to_string()),
-                    failure_dialogue: Some("Mailbox400LetterFailure".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Take the letter".to_string(),
-                    success_dialogue: Some("MailboxFourthRow".to_string()),
-                    item_to_pickup: Some("Administrator's letter".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+    pub fn dialogue(&self, location_id: &str, dialogue_id: &str) -> Option<&Dialogue> {
+        self.locations.get(location_id)?.dialogues.get(dialogue_id)
+    }
 
-    vestibule_dialogues.insert(
-        "Mailbox400LetterSuccess".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "Neat, disciplined, orderly handwriting. Professional. From a hand that kills.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "The plot thickens!".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Cut the drama. What else can I tell?".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+    // The dialogue an option's target leads to from `location_id`, if it exists
+    pub fn target_dialogue(&self, location_id: &str, target: &str) -> Option<&Dialogue> {
+        let (location_id, dialogue_id) = resolve_target(&self.locations, location_id, target);
+        self.dialogue(&location_id, &dialogue_id)
+    }
+}
 
-    vestibule_dialogues.insert(
-        "Mailbox400LetterFailure".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "This is really nice handwriting. Precise. It just screams 'competence'. Whoever wrote this was clearly...".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "A politician.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "A scientist.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "A bureaucrat.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "An artist.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "How should I know?".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPen".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+#[derive(Debug)]
+pub enum ContentError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, line: usize, column: usize, message: String },
+    Invalid { path: PathBuf, message: String }, // Parses, but doesn't fit with the rest of the content
+}
 
-    vestibule_dialogues.insert(
-        "Mailbox400LetterPen".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "The pen almost runs out of ink just as he finishes: the dark, confident line sputters out. It left a curling imprint on the paper, a ghostly line where no ink filled in.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Continue".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPenQuestions".to_string()),
-                    ..Default::default()
-                },
-            ],
-            passive_check: vec![
-                PassiveCheck {
-                    skill: "checkmate".to_string(),
-                    target: 10,
-                    success_text: Some("He was in a rush, but wanted to hide it. Someone with a hand this meticulous would have switched to a new cartridge otherwise.".to_string()),
-                    failure_text: Some("It means he was using his pen a lot. Makes sense for someone with really nice handwriting to use a pen a lot.".to_string()),
-                    speaker: Some("Checkmate".to_string())
-                },
-            ] ,
-            ..Default::default()
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContentError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ContentError::Parse { path, line, column, message } => {
+                write!(f, "{}:{}:{}: {}", path.display(), line, column, message)
+            }
+            ContentError::Invalid { path, message } => write!(f, "{}: {}", path.display(), message),
         }
-    );
+    }
+}
 
-    vestibule_dialogues.insert(
-        "Mailbox400LetterPenQuestions".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "The letter waits in the mailbox.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Anything else? At all? (Oldtime Religion 8)".to_string(),
-                    challenge_attribute: Some("oldtime_religion".to_string()),
-                    challenge_number: Some(8),
-                    success_dialogue: Some("Mailbox400LetterAnythingSuccess".to_string()),
-                    failure_dialogue: Some("Mailbox400LetterAnythingFailure".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Shake the envelope.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterListen".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Sniff the envelope.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterSmell".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Taste the envelope.".to_string(),
-                    success_dialogue: Some("Mailbox400LetterTaste".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Enough of this.".to_string(),
-                    success_dialogue: Some("Mailbox400".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+impl std::error::Error for ContentError {}
 
-    vestibule_dialogues.insert(
-        "Mailbox400LetterAnythingSuccess".to_string(),
-        Dialogue{
-            speaker: "Oldtime Religion".to_string(),
-            intro: "He was religious.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Egads! The opiate of the masses? In *my* socialist republic?".to_string(),
-                    success_dialogue: Some("LetterReligious".to_string()),
-                    ..Default::default()
-                },
-                DialogueOption {
-                    description: "Seriously? How could I possibly know that?".to_string(),
-                    success_dialogue: Some("LetterReligious".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+// Each file in <content dir>/locations is one location, keyed by its file stem (Vestibule.ron -> "Vestibule")
+pub fn load_locations(content_dir: impl AsRef<Path>) -> Result<HashMap<String, Location>, ContentError> {
+    let dir = content_dir.as_ref().join("locations");
+    let entries = fs::read_dir(&dir).map_err(|source| ContentError::Io { path: dir.clone(), source })?;
 
-    vestibule_dialogues.insert(
-        "Mailbox400LetterListen".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "The envelope is thin, without overt bulging. But the dull jangle of metal sounds inside. A key, probably.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "But I already have a key.".to_string(),
-                    success_dialogue: Some("LetterKeyAlready".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "Mailbox400LetterSmell".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "It smells like stale sweat and candle smoke. It probably sat on someone's desk for a long time. He might have been saving it.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Return".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPenQuestions".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
-
-    vestibule_dialogues.insert(
-        "Mailbox400LetterTaste".to_string(),
-        Dialogue{
-            speaker: "Crisp Letter".to_string(),
-            intro: "It tastes like paper. I don't know what you expected.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Return".to_string(),
-                    success_dialogue: Some("Mailbox400LetterPenQuestions".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+    let mut locations = HashMap::new();
+    for entry in entries {
+        let path = entry.map_err(|source| ContentError::Io { path: dir.clone(), source })?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+            continue;
+        }
+        let id = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(stem) => stem.to_string(),
+            None => continue,
+        };
+        locations.insert(id, load_location(&path)?);
+    }
+    Ok(locations)
+}
 
-    vestibule_dialogues.insert(
-        "LetterReligious".to_string(),
-        Dialogue{
-            speaker: "Oldtime Religion".to_string(),
-            intro: "It's more likely than you think. You can see it in how the 'g' curls: a decadent, calligraphic flourish that comes out of the handwriting methods taught before the Revolution. Some reactionary groups, especially those aligned with the Orthodox church, pick it up from copying church documents.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Case closed!".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                }, // what case, you're not a detective
-                DialogueOption {
-                    description: "But, to be clear, calligraphy doesn't actually imply anything about the writer's beliefs regarding God, the church, or anything else, right?".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+pub fn load_location(path: &Path) -> Result<Location, ContentError> {
+    read_ron(path)
+}
 
-    vestibule_dialogues.insert(
-        "".to_string(),
-        Dialogue{
-            speaker: "".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+// Any RON file the game reads, with parse errors pointing at the file, line and column
+pub fn read_ron<T: DeserializeOwned>(path: &Path) -> Result<T, ContentError> {
+    let text = fs::read_to_string(path).map_err(|source| ContentError::Io { path: path.to_path_buf(), source })?;
+    ron::from_str(&text).map_err(|err| ContentError::Parse {
+        path: path.to_path_buf(),
+        line: err.position.line,
+        column: err.position.col,
+        message: err.code.to_string(),
+    })
+}
 
+// Writes a location back to <content dir>/locations/<id>.ron, in the same layout load_locations reads
+pub fn save_location(content_dir: impl AsRef<Path>, id: &str, location: &Location) -> Result<(), ContentError> {
+    let path = content_dir.as_ref().join("locations").join(format!("{}.ron", id));
+    fs::write(&path, location_to_ron(location) + "\n").map_err(|source| ContentError::Io { path, source })
+}
 
+pub fn location_to_ron(location: &Location) -> String {
+    let config = PrettyConfig::new()
+        .struct_names(true)
+        .extensions(Extensions::IMPLICIT_SOME);
+    // Every quote gets escaped by ron, but writers shouldn't have to read can\'t in the files
+    ron::ser::to_string_pretty(location, config)
+        .expect("locations always serialize")
+        .replace("\\'", "'")
+}
 
-    //mess with people's mail, most empty
-    // box numbers
-    // your own, a letter from the last guy, a key too??? but not the key to the front door
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn shipped_content_loads() {
+        let locations = load_locations(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap();
 
+        let vestibule = &locations["Vestibule"];
+        assert_eq!(vestibule.name, "The Vestibule");
+        assert_eq!(vestibule.exits, vec![Exit::new("FirstFloor".to_string())]);
 
-    //in the admin room, a calendar? learn about time, historical events, and the last guy's personality
+        let fourth_floor = &locations["FourthFloor"];
+        let administrator = fourth_floor.exits.iter().find(|exit| exit.to == "AdministratorRoom").unwrap();
+        assert_eq!(administrator.requires, Some("item(Administrator's letter)".parse().unwrap()));
+        assert!(administrator.locked_message.is_some());
 
+        let clock = &vestibule.dialogues["InspectClock"];
+        assert_eq!(clock.options[0].challenge_attribute.as_deref(), Some("delusion"));
+        assert_eq!(clock.options[0].challenge_number, Some(12));
+        assert_eq!(clock.passive_check.len(), 2);
+        assert_eq!(locations.len(), 9);
+    }
 
-    // default-enabled version, nice and compact
-    // remember to make is_hidden false if this is an entry point!!
-    vestibule_dialogues.insert(
-        "".to_string(),
-        Dialogue{
-            speaker: "".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    success_dialogue: Some("".to_string()),
-                    ..Default::default()
-                },
-            ],
-            ..Default::default()
-        }
-    );
+    #[test]
+    fn skill_modifiers_last_a_duration() {
+        let effect: Effect = ron::from_str(r#"ModifySkill(skill: "gizmo", amount: 2, duration: "2h30m", source: "Oil can")"#).unwrap();
+        assert_eq!(effect.to_string(), "gizmo +2 for 2h30m (Oil can)");
+        assert!(ron::from_str::<Effect>(r#"ModifySkill(skill: "gizmo", amount: 2, duration: -5, source: "Oil can")"#).is_err());
+        // The name it had before it took units
+        let old: Effect = ron::from_str(r#"ModifySkill(skill: "gizmo", amount: 2, minutes: 30, source: "Oil can")"#).unwrap();
+        assert_eq!(old.to_string(), "gizmo +2 for 30m (Oil can)");
+    }
 
+    #[test]
+    fn unknown_skills_fail_to_load() {
+        let content = Path::new(env!("CARGO_MANIFEST_DIR")).join("content");
+        let dir = std::env::temp_dir().join(format!("shadow_soldiers_unknown_skill_{}", std::process::id()));
+        fs::create_dir_all(dir.join("locations")).unwrap();
+        for file in ["skills.ron", "rules.ron", "events.ron"] {
+            fs::copy(content.join(file), dir.join(file)).unwrap();
+        }
+        fs::write(
+            dir.join("locations").join("Hall.ron"),
+            r#"Location(name: "Hall", dialogues: {"Start": Dialogue(options: [DialogueOption(visible_when: Some("charisma > 2"))])})"#,
+        )
+        .unwrap();
+
+        let err = Content::load(&dir).err().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(err.to_string().ends_with("Hall.ron: Start: option 1 condition uses unknown skill \"charisma\""), "{}", err);
+    }
 
-    // default template
+    #[test]
+    fn skill_modifiers_of_no_time_fail_to_load() {
+        let content = Path::new(env!("CARGO_MANIFEST_DIR")).join("content");
+        let dir = std::env::temp_dir().join(format!("shadow_soldiers_zero_modifier_{}", std::process::id()));
+        fs::create_dir_all(dir.join("locations")).unwrap();
+        for file in ["skills.ron", "rules.ron", "events.ron"] {
+            fs::copy(content.join(file), dir.join(file)).unwrap();
+        }
+        fs::write(
+            dir.join("locations").join("Hall.ron"),
+            r#"Location(name: "Hall", dialogues: {"Start": Dialogue(effects: [ModifySkill(skill: "gizmo", amount: 1, minutes: 0, source: "Draught")])})"#,
+        )
+        .unwrap();
+
+        let err = Content::load(&dir).err().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(err.to_string().ends_with("Hall.ron: Start: a skill modifier lasts 0m"), "{}", err);
+    }
 
-    vestibule_dialogues.insert(
-        "".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "".to_string(),
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-            ],
-            passive_check: vec![],
-            xp_reward: None,
-            is_hidden: true,
-            time: Some(1),
+    #[test]
+    fn saving_unchanged_content_rewrites_the_same_file() {
+        let content = concat!(env!("CARGO_MANIFEST_DIR"), "/content");
+        let locations = load_locations(content).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("shadow_soldiers_save_{}", std::process::id()));
+        fs::create_dir_all(dir.join("locations")).unwrap();
+        // The Fourth Floor has locked exits alongside plain ones
+        let saved: Vec<_> = ["Vestibule", "FourthFloor"]
+            .into_iter()
+            .map(|id| {
+                save_location(&dir, id, &locations[id]).unwrap();
+                (id, fs::read_to_string(dir.join("locations").join(format!("{}.ron", id))).unwrap())
+            })
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+        for (id, saved) in saved {
+            assert_eq!(saved, fs::read_to_string(Path::new(content).join("locations").join(format!("{}.ron", id))).unwrap());
         }
-    );
-
-
-
-    // Define sample dialogues for the First Floor
-    let mut first_floor_dialogues = HashMap::new();
-    first_floor_dialogues.insert(
-        "Start".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "You are now on the first floor. A butler greets you.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Talk to the butler.".to_string(),
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("ButlerResponse".to_string()),
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-                DialogueOption {
-                    description: "Try to open the stuck door (Dossier challenge).".to_string(),
-                    challenge_attribute: Some("dossier".to_string()),
-                    challenge_number: Some(12),
-                    success_dialogue: Some("Garden".to_string()),
-                    failure_dialogue: Some("FailedDoor".to_string()),
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-                DialogueOption {
-                    description: "Go back to the vestibule.".to_string(),
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("Vestibule".to_string()),
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-            ],
-            passive_check: vec![],
-            xp_reward: None,
-            is_hidden: false,
-            time: Some(1),
-        },
-    );
-
-    // Butler Dialogue
-    first_floor_dialogues.insert(
-        "ButlerResponse".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "The butler nods respectfully. 'The garden is beyond the stuck door,' he mentions.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Exit conversation.".to_string(), // This sends you back to First Floor's "Start"
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("Start".to_string()), // Go back to the room's main dialogue
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-            ],
-            passive_check: vec![],
-            xp_reward: None,
-            is_hidden: false,
-            time: Some(1),
-        },
-    );
-
-    first_floor_dialogues.insert(
-        "FailedDoor".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "The door remains stuck, refusing to budge.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Go back to the first floor.".to_string(),
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("Start".to_string()), // Go back to the first floor's main dialogue
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-            ],
-            passive_check: vec![],
-            xp_reward: None,
-            is_hidden: false,
-            time: Some(1),
-        },
-    );
-
-    // Define sample dialogues for the Garden
-    let mut garden_dialogues = HashMap::new();
-    garden_dialogues.insert(
-        "Start".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "You are now in the garden. An old woman sits on a bench.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Talk to the old woman.".to_string(),
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("OldWomanResponse".to_string()),
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-                DialogueOption {
-                    description: "Go back to the first floor.".to_string(),
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("FirstFloor".to_string()),
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-            ],
-            passive_check: vec![],
-            xp_reward: None,
-            is_hidden: false,
-            time: Some(1)
-        },
-    );
-
-    // Old Woman Dialogue
-    garden_dialogues.insert(
-        "OldWomanResponse".to_string(),
-        Dialogue {
-            speaker: "".to_string(),
-            intro: "The old woman smiles softly and speaks in a quiet voice.".to_string(),
-            options: vec![
-                DialogueOption {
-                    description: "Exit conversation.".to_string(), // Go back to Garden's "Start"
-                    challenge_attribute: None,
-                    challenge_number: None,
-                    success_dialogue: Some("Start".to_string()), // Go back to the Garden's main dialogue
-                    failure_dialogue: None,
-                    item_to_pickup: None,
-                    visible_when: None,
-                    flags: None,
-                },
-            ],
-            passive_check: vec![],
-            xp_reward: None,
-            is_hidden: false,
-            time: Some(1),
-        },
-    );
-
-    let outdoors_dialogues = HashMap::new();
-
-    let second_floor_dialogues = HashMap::new();
-
-    let third_floor_dialogues = HashMap::new();
-
-    let fourth_floor_dialogues = HashMap::new();
-
-    let rooftop_garden_dialogues = HashMap::new();
-
-    let admin_room_dialogues = HashMap::new();
-
-    // Define Locations
+    }
 
-    locations.insert(
-        "Outdoors".to_string(),
-        Location {
-            name: "Outdoors".to_string(),
-            dialogues: outdoors_dialogues,
-            exits: vec!["Vestibule".to_string(),]
+    #[test]
+    fn parse_errors_report_file_and_line() {
+        let dir = std::env::temp_dir().join(format!("shadow_soldiers_content_{}", std::process::id()));
+        fs::create_dir_all(dir.join("locations")).unwrap();
+        fs::write(
+            dir.join("locations").join("Broken.ron"),
+            "Location(\n    name: \"Broken\",\n    dialogues: {\n        \"Start\": Dialogue(intro: 3),\n    },\n)\n",
+        )
+        .unwrap();
+
+        let err = load_locations(&dir).err().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        match err {
+            ContentError::Parse { path, line, .. } => {
+                assert!(path.ends_with("Broken.ron"));
+                assert_eq!(line, 4);
+            }
+            other => panic!("expected a parse error, got {}", other),
         }
-    );
-
-    locations.insert(
-        "Vestibule".to_string(),
-        Location {
-            name: "The Vestibule".to_string(),
-            dialogues: vestibule_dialogues,
-            exits: vec!["FirstFloor".to_string()],
-        },
-    );
-
-    locations.insert(
-        "FirstFloor".to_string(),
-        Location {
-            name: "The First Floor".to_string(),
-            dialogues: first_floor_dialogues,
-            exits: vec!["Vestibule".to_string(), "SecondFloor".to_string(), "ThirdFloor".to_string(), "FourthFloor".to_string(),],
-        },
-    );
-
-    locations.insert(
-        "SecondFloor".to_string(),
-        Location {
-            name: "The Second Floor".to_string(),
-            dialogues: second_floor_dialogues,
-            exits: vec!["FirstFloor".to_string(), "ThirdFloor".to_string(), "FourthFloor".to_string(),],
-        },
-    );
-
-    locations.insert(
-        "ThirdFloor".to_string(),
-        Location {
-            name: "The Third Floor".to_string(),
-            dialogues: third_floor_dialogues,
-            exits: vec!["FirstFloor".to_string(), "SecondFloor".to_string(), "FourthFloor".to_string(),],
-        },
-    );
-
-    locations.insert(
-        "FourthFloor".to_string(),
-        Location {
-            name: "The Fourth Floor".to_string(),
-            dialogues: fourth_floor_dialogues,
-            exits: vec!["FirstFloor".to_string(), "SecondFloor".to_string(), "ThirdFloor".to_string(), "RooftopGarden".to_string(), "AdministratorRoom".to_string(),],
-        },
-    );
-
-    locations.insert(
-        "AdministratorRoom".to_string(),
-        Location {
-            name: "Administrator's Room".to_string(),
-            dialogues: admin_room_dialogues,
-            exits: vec!["FourthFloor".to_string()],
-        },
-    );
-
-    locations.insert(
-        "RooftopGarden".to_string(),
-        Location {
-            name: "Rooftop Garden".to_string(),
-            dialogues: rooftop_garden_dialogues,
-            exits: vec!["FourthFloor".to_string()],
-        },
-    );
-
-    locations.insert(
-        "Garden".to_string(),
-        Location {
-            name: "The Garden".to_string(),
-            dialogues: garden_dialogues,
-            exits: vec!["FirstFloor".to_string()],
-        },
-    );
-
-    locations
+    }
 }
-
-
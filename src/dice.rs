@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+// Rolls 2d6 for challenges. The engine owns one, and saves keep its state so reloading
+// doesn't give a failed check a fresh roll.
+pub trait DiceRoller {
+    fn roll(&mut self) -> (i32, i32);
+    fn state(&self) -> DiceState;
+}
+
+// Enough to rebuild a roller exactly where it left off
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DiceState {
+    Seeded { seed: u64, rolls: u64 },
+    Scripted { rolls: Vec<(i32, i32)> },
+}
+
+impl DiceState {
+    pub fn into_roller(self) -> Box<dyn DiceRoller> {
+        match self {
+            DiceState::Seeded { seed, rolls } => Box::new(SeededDice::resume(seed, rolls)),
+            DiceState::Scripted { rolls } => Box::new(ScriptedDice::new(rolls)),
+        }
+    }
+}
+
+pub struct SeededDice {
+    seed: u64,
+    rolls: u64, // How many pairs have been rolled since seeding
+    rng: StdRng,
+}
+
+impl SeededDice {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, rolls: 0, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    // A new seed every session, for when nobody asked for one
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+
+    // Replays `rolls` pairs from `seed` to get back to the same point in the sequence
+    pub fn resume(seed: u64, rolls: u64) -> Self {
+        let mut dice = Self::new(seed);
+        for _ in 0..rolls {
+            dice.roll();
+        }
+        dice
+    }
+}
+
+impl DiceRoller for SeededDice {
+    fn roll(&mut self) -> (i32, i32) {
+        self.rolls += 1;
+        (self.rng.gen_range(1..=6), self.rng.gen_range(1..=6))
+    }
+
+    fn state(&self) -> DiceState {
+        DiceState::Seeded { seed: self.seed, rolls: self.rolls }
+    }
+}
+
+// Hands out the given rolls in order, for tests and scripted playthroughs.
+// Once they run out every roll is double ones.
+pub struct ScriptedDice {
+    rolls: VecDeque<(i32, i32)>,
+}
+
+impl ScriptedDice {
+    pub fn new(rolls: impl IntoIterator<Item = (i32, i32)>) -> Self {
+        Self { rolls: rolls.into_iter().collect() }
+    }
+}
+
+impl DiceRoller for ScriptedDice {
+    fn roll(&mut self) -> (i32, i32) {
+        self.rolls.pop_front().unwrap_or((1, 1))
+    }
+
+    fn state(&self) -> DiceState {
+        DiceState::Scripted { rolls: self.rolls.iter().copied().collect() }
+    }
+}
+
+// Takes `--seed N` or `--seed=N` out of the command line, leaving the rest for the subcommands
+pub fn take_seed_arg(args: &mut Vec<String>) -> Result<Option<u64>, String> {
+    let Some(position) = args.iter().position(|arg| arg == "--seed" || arg.starts_with("--seed=")) else {
+        return Ok(None);
+    };
+    let arg = args.remove(position);
+    let value = match arg.strip_prefix("--seed=") {
+        Some(value) => value.to_string(),
+        None if position < args.len() => args.remove(position),
+        None => return Err("--seed needs a number".to_string()),
+    };
+    value.parse().map(Some).map_err(|_| format!("--seed needs a number, not \"{}\"", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_dice_resume_where_they_left_off() {
+        let mut dice = SeededDice::new(42);
+        let first: Vec<_> = (0..5).map(|_| dice.roll()).collect();
+        let mut again = SeededDice::new(42);
+        assert_eq!(first, (0..5).map(|_| again.roll()).collect::<Vec<_>>());
+        assert!(first.iter().all(|&(a, b)| (1..=6).contains(&a) && (1..=6).contains(&b)));
+
+        let mut resumed = dice.state().into_roller();
+        assert_eq!(resumed.roll(), dice.roll());
+        assert_eq!(resumed.state(), DiceState::Seeded { seed: 42, rolls: 6 });
+    }
+
+    #[test]
+    fn seed_arg_is_taken_out_of_the_command_line() {
+        let mut args: Vec<String> = ["game", "--seed", "7", "edit"].map(String::from).to_vec();
+        assert_eq!(take_seed_arg(&mut args), Ok(Some(7)));
+        assert_eq!(args, ["game", "edit"]);
+
+        let mut args: Vec<String> = ["game", "--seed=x"].map(String::from).to_vec();
+        assert!(take_seed_arg(&mut args).is_err());
+    }
+}
@@ -0,0 +1,700 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use eframe::egui;
+use egui::{Color32, Id};
+use shadow_soldiers_gui::dialogues::{
+    load_locations, save_location, CheckKind, Dialogue, DialogueOption, Effect, Location, PassiveCheck,
+};
+use shadow_soldiers_gui::condition::Condition;
+use shadow_soldiers_gui::skills::SkillRegistry;
+use shadow_soldiers_gui::time::Duration;
+use shadow_soldiers_gui::validate::{validate, Issue, Severity};
+
+// What the editor wants the app to do after this frame
+pub enum EditorAction {
+    None,
+    Close,
+    Playtest { location: String, dialogue: String, locations: HashMap<String, Location> },
+}
+
+// Works on its own copy of the content, read fresh from disk, so play-throughs never leak into saved files
+pub struct Editor {
+    content_dir: PathBuf,
+    locations: HashMap<String, Location>,
+    skills: SkillRegistry,
+    selected_location: Option<String>,
+    selected_dialogue: Option<String>,
+    new_dialogue_id: String,
+    rename_buffer: String,
+    unsaved: BTreeSet<String>, // Locations edited since the last save
+    issues: Vec<Issue>,
+    status: String,
+}
+
+impl Editor {
+    pub fn new(content_dir: PathBuf) -> Self {
+        Self {
+            content_dir,
+            locations: HashMap::new(),
+            skills: SkillRegistry::default(),
+            selected_location: None,
+            selected_dialogue: None,
+            new_dialogue_id: String::new(),
+            rename_buffer: String::new(),
+            unsaved: BTreeSet::new(),
+            issues: vec![],
+            status: String::new(),
+        }
+    }
+
+    // Reloads the content from disk, dropping any unsaved edits
+    pub fn open(&mut self) {
+        let loaded = SkillRegistry::load(&self.content_dir).and_then(|skills| Ok((load_locations(&self.content_dir)?, skills)));
+        match loaded {
+            Ok((locations, skills)) => {
+                self.locations = locations;
+                self.skills = skills;
+                self.unsaved.clear();
+                self.issues = validate(&self.locations, &self.skills);
+                self.status = format!("Loaded {}", self.content_dir.display());
+            }
+            Err(err) => self.status = format!("Failed to load content: {}", err),
+        }
+        // Drop a selection the reloaded content doesn't have any more
+        let location = self.selected_location.as_ref().and_then(|id| self.locations.get(id));
+        if location.is_none() {
+            self.selected_location = None;
+        }
+        if !self.selected_dialogue.as_ref().is_some_and(|id| location.is_some_and(|location| location.dialogues.contains_key(id))) {
+            self.selected_dialogue = None;
+        }
+    }
+
+    // Shows a message from the app in the status line
+    pub fn report(&mut self, status: String) {
+        self.status = status;
+    }
+
+    fn save(&mut self) {
+        let mut saved = 0;
+        for id in std::mem::take(&mut self.unsaved) {
+            match save_location(&self.content_dir, &id, &self.locations[&id]) {
+                Ok(()) => saved += 1,
+                Err(err) => {
+                    self.status = format!("Failed to save: {}", err);
+                    self.unsaved.insert(id);
+                    return;
+                }
+            }
+        }
+
+        self.issues = validate(&self.locations, &self.skills);
+        let errors = self.issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+        self.status = format!(
+            "Saved {} location(s). Validation: {} errors, {} warnings",
+            saved,
+            errors,
+            self.issues.len() - errors
+        );
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> EditorAction {
+        let mut action = EditorAction::None;
+
+        egui::TopBottomPanel::top(Id::new("editor_top_panel")).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Content Editor");
+                if ui.button("Save").clicked() {
+                    self.save();
+                }
+                if ui.button("Revert to saved").clicked() {
+                    self.open();
+                }
+                if ui.button("Close Editor").clicked() {
+                    action = EditorAction::Close;
+                }
+                if !self.unsaved.is_empty() {
+                    ui.colored_label(Color32::YELLOW, format!("Unsaved: {}", self.unsaved.iter().cloned().collect::<Vec<_>>().join(", ")));
+                }
+            });
+            ui.label(&self.status);
+        });
+
+        egui::TopBottomPanel::bottom(Id::new("editor_issues_panel")).resizable(true).show(ctx, |ui| {
+            ui.label(egui::RichText::new(format!("Validation ({} issues)", self.issues.len())).strong());
+            egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                for issue in &self.issues {
+                    let color = match issue.severity {
+                        Severity::Error => Color32::LIGHT_RED,
+                        Severity::Warning => Color32::YELLOW,
+                    };
+                    ui.colored_label(color, issue.to_string());
+                }
+            });
+        });
+
+        egui::SidePanel::left(Id::new("editor_browser_panel")).min_width(220.0).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| self.browser(ui));
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if let Some(playtest) = self.dialogue_form(ui) {
+                    action = playtest;
+                }
+            });
+        });
+
+        action
+    }
+
+    fn browser(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Locations");
+        let mut location_ids: Vec<String> = self.locations.keys().cloned().collect();
+        location_ids.sort();
+        for id in location_ids {
+            let selected = self.selected_location.as_ref() == Some(&id);
+            if ui.selectable_label(selected, format!("{} ({})", id, self.locations[&id].name)).clicked() {
+                self.selected_location = Some(id);
+                self.selected_dialogue = None;
+            }
+        }
+
+        let Some(location_id) = self.selected_location.clone() else { return };
+        let Some(location) = self.locations.get(&location_id) else { return };
+        ui.separator();
+        ui.heading("Dialogues");
+
+        let mut dialogue_ids: Vec<String> = location.dialogues.keys().cloned().collect();
+        dialogue_ids.sort_by_key(|id| (id != "Start", id.clone()));
+        for id in dialogue_ids {
+            let selected = self.selected_dialogue.as_ref() == Some(&id);
+            let label = if id.is_empty() { "(empty ID)".to_string() } else { id.clone() };
+            if ui.selectable_label(selected, label).clicked() {
+                self.rename_buffer = id.clone();
+                self.selected_dialogue = Some(id);
+            }
+        }
+
+        ui.separator();
+        ui.text_edit_singleline(&mut self.new_dialogue_id);
+        let id = self.new_dialogue_id.trim().to_string();
+        let Some(location) = self.locations.get_mut(&location_id) else { return };
+        let taken = location.dialogues.contains_key(&id);
+        if ui.add_enabled(!id.is_empty() && !taken, egui::Button::new("New dialogue")).clicked() {
+            location.add_dialogue(id.clone(), Dialogue::default());
+            self.unsaved.insert(location_id);
+            self.rename_buffer = id.clone();
+            self.selected_dialogue = Some(id);
+            self.new_dialogue_id.clear();
+        }
+    }
+
+    fn dialogue_form(&mut self, ui: &mut egui::Ui) -> Option<EditorAction> {
+        let location_id = self.selected_location.clone()?;
+        let Some(dialogue_id) = self.selected_dialogue.clone() else {
+            ui.label("Select a dialogue on the left, or create a new one.");
+            return None;
+        };
+        let targets = self.target_suggestions(&location_id);
+        let location = self.locations.get_mut(&location_id)?;
+        let mut playtest = false;
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.heading(format!("{} / {}", location_id, dialogue_id));
+            playtest = ui.button("Play-test from here").clicked();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("ID:");
+            ui.text_edit_singleline(&mut self.rename_buffer);
+            let new_id = self.rename_buffer.trim().to_string();
+            let can_rename = !new_id.is_empty() && new_id != dialogue_id && !location.dialogues.contains_key(&new_id);
+            if ui.add_enabled(can_rename, egui::Button::new("Rename")).clicked() {
+                let Some(dialogue) = location.dialogues.remove(&dialogue_id) else { return };
+                location.add_dialogue(new_id.clone(), dialogue);
+                // Keep this location's links pointing at the renamed dialogue
+                for other in location.dialogues.values_mut() {
+                    for option in &mut other.options {
+                        for target in [&mut option.success_dialogue, &mut option.failure_dialogue].into_iter().flatten() {
+                            if *target == dialogue_id {
+                                *target = new_id.clone();
+                            }
+                        }
+                    }
+                }
+                self.selected_dialogue = Some(new_id);
+                changed = true;
+            }
+            if ui.button("Delete dialogue").clicked() {
+                location.dialogues.remove(&dialogue_id);
+                self.selected_dialogue = None;
+                changed = true;
+            }
+        });
+
+        if let Some(dialogue) = self.selected_dialogue.as_ref().and_then(|id| location.dialogues.get_mut(id)) {
+            ui.separator();
+            changed |= dialogue_fields(ui, dialogue, &self.skills);
+            ui.separator();
+            changed |= option_list(ui, &mut dialogue.options, &targets, &self.skills);
+            ui.separator();
+            changed |= passive_check_list(ui, &mut dialogue.passive_check, &self.skills);
+        }
+
+        if changed {
+            self.unsaved.insert(location_id.clone());
+        }
+
+        if !playtest {
+            return None;
+        }
+        Some(EditorAction::Playtest {
+            location: location_id,
+            dialogue: self.selected_dialogue.clone()?,
+            locations: self.locations.clone(),
+        })
+    }
+
+    // Dialogues in this location, then every location ID
+    fn target_suggestions(&self, location_id: &str) -> Vec<String> {
+        let mut dialogues: Vec<String> = self.locations[location_id].dialogues.keys().filter(|id| !id.is_empty()).cloned().collect();
+        dialogues.sort();
+        let mut locations: Vec<String> = self.locations.keys().cloned().collect();
+        locations.sort();
+        dialogues.extend(locations);
+        dialogues
+    }
+}
+
+fn dialogue_fields(ui: &mut egui::Ui, dialogue: &mut Dialogue, skills: &SkillRegistry) -> bool {
+    let mut changed = false;
+    egui::Grid::new("dialogue_fields").num_columns(2).show(ui, |ui| {
+        ui.label("Speaker");
+        changed |= ui.text_edit_singleline(&mut dialogue.speaker).changed();
+        ui.end_row();
+
+        ui.label("Intro");
+        changed |= ui
+            .add(egui::TextEdit::multiline(&mut dialogue.intro).desired_rows(5).desired_width(f32::INFINITY))
+            .changed();
+        ui.end_row();
+
+        ui.label("Hidden");
+        changed |= ui.checkbox(&mut dialogue.is_hidden, "").changed();
+        ui.end_row();
+
+        ui.label("XP reward");
+        changed |= optional_number(ui, &mut dialogue.xp_reward, 10);
+        ui.end_row();
+
+        ui.label("Time").on_hover_text("How long entering the dialogue takes: minutes, or e.g. 2h30m");
+        changed |= optional_duration(ui, &mut dialogue.time);
+        ui.end_row();
+
+        ui.label("Visible when").on_hover_text("Options leading here are hidden while this doesn't hold");
+        changed |= condition_field(ui, "dialogue_condition", &mut dialogue.visible_when);
+        ui.end_row();
+
+        ui.label("Effects on entry");
+        changed |= effect_list(ui, &mut dialogue.effects, skills);
+        ui.end_row();
+    });
+    changed
+}
+
+fn option_list(ui: &mut egui::Ui, options: &mut Vec<DialogueOption>, targets: &[String], skills: &SkillRegistry) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    let mut move_up = None;
+
+    ui.heading("Options");
+    for (i, option) in options.iter_mut().enumerate() {
+        ui.push_id(("option", i), |ui| {
+            egui::Frame::group(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("Option {}", i + 1)).strong());
+                    if i > 0 && ui.small_button("Move up").clicked() {
+                        move_up = Some(i);
+                    }
+                    if ui.small_button("Delete").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                changed |= option_fields(ui, option, targets, skills);
+            });
+        });
+    }
+
+    if ui.button("Add option").clicked() {
+        options.push(DialogueOption::default());
+        changed = true;
+    }
+    if let Some(i) = remove {
+        options.remove(i);
+        changed = true;
+    }
+    if let Some(i) = move_up {
+        options.swap(i - 1, i);
+        changed = true;
+    }
+    changed
+}
+
+fn option_fields(ui: &mut egui::Ui, option: &mut DialogueOption, targets: &[String], skills: &SkillRegistry) -> bool {
+    let mut changed = false;
+    egui::Grid::new("option_fields").num_columns(2).show(ui, |ui| {
+        ui.label("Description");
+        changed |= ui
+            .add(egui::TextEdit::singleline(&mut option.description).desired_width(f32::INFINITY))
+            .changed();
+        ui.end_row();
+
+        ui.label("Success target");
+        changed |= target_field(ui, "success", &mut option.success_dialogue, targets);
+        ui.end_row();
+
+        let mut is_challenge = option.challenge_number.is_some();
+        ui.label("Challenge");
+        if ui.checkbox(&mut is_challenge, "").changed() {
+            if is_challenge {
+                option.challenge_number = Some(10);
+                option.challenge_attribute.get_or_insert_with(|| first_skill(skills));
+            } else {
+                option.challenge_number = None;
+                option.challenge_attribute = None;
+                option.failure_dialogue = None;
+                option.check_kind = CheckKind::White;
+            }
+            changed = true;
+        }
+        ui.end_row();
+
+        if let Some(number) = &mut option.challenge_number {
+            ui.label("Skill");
+            ui.horizontal(|ui| {
+                changed |= skill_combo(ui, "challenge_skill", option.challenge_attribute.get_or_insert_with(String::new), skills);
+                changed |= ui.add(egui::DragValue::new(number).clamp_range(2..=20)).changed();
+            });
+            ui.end_row();
+
+            ui.label("Kind").on_hover_text("White checks can be retried after raising the skill; red checks get one try");
+            ui.horizontal(|ui| {
+                changed |= ui.selectable_value(&mut option.check_kind, CheckKind::White, "White").changed();
+                changed |= ui.selectable_value(&mut option.check_kind, CheckKind::Red, "Red").changed();
+            });
+            ui.end_row();
+
+            ui.label("Failure target");
+            changed |= target_field(ui, "failure", &mut option.failure_dialogue, targets);
+            ui.end_row();
+        }
+
+        ui.label("Item to pick up");
+        changed |= optional_text(ui, &mut option.item_to_pickup);
+        ui.end_row();
+
+        ui.label("Visible when").on_hover_text("e.g. clock_open and not item(Toy Plane), gizmo >= 4, time >= 22:00");
+        changed |= condition_field(ui, "option_condition", &mut option.visible_when);
+        ui.end_row();
+
+        ui.label("Option ID").on_hover_text("Saves remember picked-up options by this ID; without one, by position");
+        changed |= optional_text(ui, &mut option.id);
+        ui.end_row();
+
+        ui.label("Flags to set");
+        changed |= flag_list(ui, &mut option.flags);
+        ui.end_row();
+
+        ui.label("Effects").on_hover_text("Applied in order after any roll; a move happens last, instead of the target");
+        changed |= effect_list(ui, &mut option.effects, skills);
+        ui.end_row();
+    });
+    changed
+}
+
+fn passive_check_list(ui: &mut egui::Ui, checks: &mut Vec<PassiveCheck>, skills: &SkillRegistry) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+
+    ui.heading("Passive checks");
+    for (i, check) in checks.iter_mut().enumerate() {
+        ui.push_id(("passive", i), |ui| {
+            egui::Frame::group(ui.style()).show(ui, |ui| {
+                egui::Grid::new("passive_fields").num_columns(2).show(ui, |ui| {
+                    ui.label("Skill");
+                    ui.horizontal(|ui| {
+                        changed |= skill_combo(ui, "passive_skill", &mut check.skill, skills);
+                        changed |= ui.add(egui::DragValue::new(&mut check.target).clamp_range(0..=20)).changed();
+                        if ui.small_button("Delete").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Speaker").on_hover_text("Leave empty for the skill's name");
+                    changed |= optional_text(ui, &mut check.speaker);
+                    ui.end_row();
+
+                    ui.label("Success text");
+                    changed |= optional_text(ui, &mut check.success_text);
+                    ui.end_row();
+
+                    ui.label("Failure text");
+                    changed |= optional_text(ui, &mut check.failure_text);
+                    ui.end_row();
+
+                    ui.label("Visible when");
+                    changed |= condition_field(ui, "passive_condition", &mut check.visible_when);
+                    ui.end_row();
+
+                    ui.label("Repeatable").on_hover_text("Speak up on every visit, not just the first");
+                    changed |= ui.checkbox(&mut check.repeatable, "").changed();
+                    ui.end_row();
+                });
+            });
+        });
+    }
+
+    if ui.button("Add passive check").clicked() {
+        checks.push(PassiveCheck {
+            skill: first_skill(skills),
+            target: 10,
+            success_text: None,
+            failure_text: None,
+            speaker: None,
+            visible_when: None,
+            repeatable: false,
+        });
+        changed = true;
+    }
+    if let Some(i) = remove {
+        checks.remove(i);
+        changed = true;
+    }
+    changed
+}
+
+// An empty text box means None
+fn optional_text(ui: &mut egui::Ui, value: &mut Option<String>) -> bool {
+    let mut text = value.clone().unwrap_or_default();
+    let changed = ui.add(egui::TextEdit::singleline(&mut text).desired_width(f32::INFINITY)).changed();
+    if changed {
+        *value = if text.is_empty() { None } else { Some(text) };
+    }
+    changed
+}
+
+// The text being typed is kept aside until it parses, with the error shown under it meanwhile.
+// An empty text box means None.
+fn condition_field(ui: &mut egui::Ui, id: &str, value: &mut Option<Condition>) -> bool {
+    let id = ui.make_persistent_id(id);
+    // A draft typed for some other condition (another option, or before an undo) doesn't carry over
+    let draft = ui.data().get_temp::<(String, Option<Condition>)>(id).filter(|(_, typed_for)| typed_for == value);
+    let mut text = draft.map_or_else(|| value.as_ref().map(Condition::to_string).unwrap_or_default(), |(text, _)| text);
+
+    let mut changed = false;
+    ui.vertical(|ui| {
+        let edited = ui.add(egui::TextEdit::singleline(&mut text).desired_width(f32::INFINITY)).changed();
+        let parsed = if text.trim().is_empty() { Ok(None) } else { text.parse::<Condition>().map(Some) };
+        match parsed {
+            Ok(condition) if edited && condition != *value => {
+                *value = condition;
+                changed = true;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                ui.colored_label(Color32::LIGHT_RED, err.message);
+            }
+        }
+    });
+    ui.data().insert_temp(id, (text, value.clone()));
+    changed
+}
+
+fn optional_number(ui: &mut egui::Ui, value: &mut Option<i32>, default: i32) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let mut enabled = value.is_some();
+        if ui.checkbox(&mut enabled, "").changed() {
+            *value = if enabled { Some(default) } else { None };
+            changed = true;
+        }
+        if let Some(number) = value {
+            changed |= ui.add(egui::DragValue::new(number)).changed();
+        }
+    });
+    changed
+}
+
+// Dragged a minute at a time, or typed with units like 2h30m
+fn duration_field(ui: &mut egui::Ui, duration: &mut Duration) -> bool {
+    let value = egui::DragValue::new(&mut duration.minutes)
+        .clamp_range(0..=i32::MAX)
+        .custom_formatter(|minutes, _| Duration::from_minutes(minutes as i32).to_string())
+        .custom_parser(|text| text.parse::<Duration>().ok().map(|duration| duration.minutes as f64));
+    ui.add(value).changed()
+}
+
+fn optional_duration(ui: &mut egui::Ui, value: &mut Option<Duration>) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let mut enabled = value.is_some();
+        if ui.checkbox(&mut enabled, "").changed() {
+            *value = enabled.then(|| Duration::from_minutes(1));
+            changed = true;
+        }
+        if let Some(duration) = value {
+            changed |= duration_field(ui, duration);
+        }
+    });
+    changed
+}
+
+fn target_field(ui: &mut egui::Ui, id: &str, value: &mut Option<String>, targets: &[String]) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= optional_text(ui, value);
+        egui::ComboBox::from_id_source(id).selected_text("Pick").show_ui(ui, |ui| {
+            for target in targets {
+                if ui.selectable_label(value.as_ref() == Some(target), target).clicked() {
+                    *value = Some(target.clone());
+                    changed = true;
+                }
+            }
+        });
+    });
+    changed
+}
+
+// Shows skill names and stores their IDs
+fn skill_combo(ui: &mut egui::Ui, id: &str, skill: &mut String, skills: &SkillRegistry) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_source(id).selected_text(skills.name(skill)).show_ui(ui, |ui| {
+        for entry in &skills.skills {
+            if ui.selectable_label(*skill == entry.id, &entry.name).clicked() {
+                *skill = entry.id.clone();
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+// What new checks and skill effects start with
+fn first_skill(skills: &SkillRegistry) -> String {
+    skills.skills.first().map_or(String::new(), |skill| skill.id.clone())
+}
+
+const EFFECT_KINDS: [&str; 8] =
+    ["Add item", "Remove item", "Set flag", "Clear flag", "Grant XP", "Modify skill", "Advance time", "Move to"];
+
+fn effect_kind(effect: &Effect) -> usize {
+    match effect {
+        Effect::AddItem(_) => 0,
+        Effect::RemoveItem(_) => 1,
+        Effect::SetFlag(_) => 2,
+        Effect::ClearFlag(_) => 3,
+        Effect::GrantXp(_) => 4,
+        Effect::ModifySkill { .. } => 5,
+        Effect::AdvanceTime(_) => 6,
+        Effect::MoveTo(_) => 7,
+    }
+}
+
+// What an effect starts as when it's added or switched to another kind
+fn new_effect(kind: usize, skills: &SkillRegistry) -> Effect {
+    match kind {
+        0 => Effect::AddItem(String::new()),
+        1 => Effect::RemoveItem(String::new()),
+        2 => Effect::SetFlag(String::new()),
+        3 => Effect::ClearFlag(String::new()),
+        4 => Effect::GrantXp(10),
+        5 => Effect::ModifySkill { skill: first_skill(skills), amount: 1, duration: Duration::from_minutes(60), source: String::new() },
+        6 => Effect::AdvanceTime(Duration::from_minutes(10)),
+        _ => Effect::MoveTo(String::new()),
+    }
+}
+
+fn effect_list(ui: &mut egui::Ui, effects: &mut Vec<Effect>, skills: &SkillRegistry) -> bool {
+    let mut changed = false;
+    ui.vertical(|ui| {
+        let mut remove = None;
+        for (i, effect) in effects.iter_mut().enumerate() {
+            ui.push_id(("effect", i), |ui| {
+                ui.horizontal(|ui| {
+                    let mut kind = effect_kind(effect);
+                    egui::ComboBox::from_id_source("effect_kind").selected_text(EFFECT_KINDS[kind]).show_ui(ui, |ui| {
+                        for (option, name) in EFFECT_KINDS.iter().enumerate() {
+                            if ui.selectable_value(&mut kind, option, *name).changed() {
+                                *effect = new_effect(kind, skills);
+                                changed = true;
+                            }
+                        }
+                    });
+                    match effect {
+                        Effect::AddItem(text)
+                        | Effect::RemoveItem(text)
+                        | Effect::SetFlag(text)
+                        | Effect::ClearFlag(text)
+                        | Effect::MoveTo(text) => changed |= ui.text_edit_singleline(text).changed(),
+                        Effect::GrantXp(number) => changed |= ui.add(egui::DragValue::new(number)).changed(),
+                        Effect::AdvanceTime(duration) => changed |= duration_field(ui, duration),
+                        Effect::ModifySkill { skill, amount, duration, source } => {
+                            changed |= skill_combo(ui, "effect_skill", skill, skills);
+                            changed |= ui.add(egui::DragValue::new(amount)).changed();
+                            ui.label("for");
+                            changed |= duration_field(ui, duration);
+                            ui.label("from");
+                            changed |= ui.text_edit_singleline(source).changed();
+                        }
+                    }
+                    if ui.small_button("x").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            });
+        }
+        if let Some(i) = remove {
+            effects.remove(i);
+            changed = true;
+        }
+        if ui.small_button("Add effect").clicked() {
+            effects.push(new_effect(0, skills));
+            changed = true;
+        }
+    });
+    changed
+}
+
+fn flag_list(ui: &mut egui::Ui, flags: &mut Option<Vec<String>>) -> bool {
+    let mut changed = false;
+    ui.vertical(|ui| {
+        let list = flags.get_or_insert_with(Vec::new);
+        let mut remove = None;
+        for (i, flag) in list.iter_mut().enumerate() {
+            ui.push_id(("flag", i), |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui.text_edit_singleline(flag).changed();
+                    if ui.small_button("x").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            });
+        }
+        if let Some(i) = remove {
+            list.remove(i);
+            changed = true;
+        }
+        if ui.small_button("Add flag").clicked() {
+            list.push(String::new());
+            changed = true;
+        }
+        if list.is_empty() {
+            *flags = None;
+        }
+    });
+    changed
+}
@@ -0,0 +1,1164 @@
+use std::fmt;
+
+use crate::checks::CheckPreview;
+use crate::condition::{Condition, Context};
+use crate::dice::{DiceRoller, DiceState, SeededDice};
+use crate::dialogues::{location_target, resolve_target, CheckKind, Content, Dialogue, DialogueOption, Effect, ENTRY_DIALOGUE};
+use crate::history::{History, HistoryEntry};
+use crate::map::Map;
+use crate::player::{Player, TemporaryModifier};
+use crate::time::{Duration, Time};
+use crate::world::{option_key, passive_key, WorldState};
+
+pub const START_LOCATION: &str = "Vestibule";
+pub const START_DIALOGUE: &str = "Start";
+pub const START_TIME: Time = Time { day: 1, hour: 3, minute: 30 };
+
+// Entering a dialogue can move the player on to another location, which can move them again.
+// Content that keeps doing that stops after this many moves rather than hanging the game.
+const MAX_MOVES_PER_ENTRY: usize = 8;
+
+// Something that happened because of a choice, for the frontend to show however it likes
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    ItemPickedUp(String),
+    ItemRemoved(String),
+    FlagSet(String),
+    FlagCleared(String),
+    // `skill_name` is what the skill registry calls `skill`, for showing
+    SkillModified { skill: String, skill_name: String, amount: i32, duration: Duration, source: String },
+    ModifierExpired { skill: String, skill_name: String, source: String },
+    Challenge(ChallengeResult),
+    DialogueEntered(String),
+    LocationChanged(String),
+    TimePassed(Duration),
+    ExitLocked { location: String, reason: String },
+    XpGained(i32),
+    SkillPointsGained { gained: i32, total: i32 },
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameEvent::ItemPickedUp(item) => write!(f, "Picked up: {}", item),
+            GameEvent::ItemRemoved(item) => write!(f, "Lost: {}", item),
+            GameEvent::FlagSet(flag) => write!(f, "Flag set: {}", flag),
+            GameEvent::FlagCleared(flag) => write!(f, "Flag cleared: {}", flag),
+            GameEvent::SkillModified { skill_name, amount, duration, source, .. } => {
+                write!(f, "{}: {} {:+} for {}", source, skill_name, amount, duration)
+            }
+            GameEvent::ModifierExpired { skill_name, source, .. } => {
+                write!(f, "{} wore off: {} is back to normal", source, skill_name)
+            }
+            GameEvent::Challenge(result) => write!(f, "{}", result),
+            GameEvent::DialogueEntered(dialogue) => write!(f, "Entered {}", dialogue),
+            GameEvent::LocationChanged(location) => write!(f, "Moved to {}", location),
+            GameEvent::TimePassed(duration) => write!(f, "{} passed", duration),
+            GameEvent::ExitLocked { location, reason } => write!(f, "The way to {} is closed: {}", location, reason),
+            GameEvent::XpGained(xp) => write!(f, "You gained {} XP!", xp),
+            GameEvent::SkillPointsGained { gained, total } => {
+                write!(f, "You gained {} skill point(s)! You now have {} skill points.", gained, total)
+            }
+        }
+    }
+}
+
+// Everything that went into a skill check, so frontends can show the roll rather than just where it led
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChallengeResult {
+    pub skill: String,
+    pub skill_name: String, // What the skill registry calls it
+    pub dice: (i32, i32),
+    pub skill_value: i32,
+    pub modifiers: Vec<Modifier>,
+    pub target: i32,
+    pub success: bool,
+    pub critical: bool, // A roll that decides the check whatever the numbers say, see `Rules`
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Modifier {
+    pub source: String,
+    pub value: i32,
+}
+
+impl ChallengeResult {
+    // What's added to the dice: the skill and its modifiers
+    pub fn bonus(&self) -> i32 {
+        self.skill_value + self.modifiers.iter().map(|modifier| modifier.value).sum::<i32>()
+    }
+
+    pub fn total(&self) -> i32 {
+        self.dice.0 + self.dice.1 + self.bonus()
+    }
+
+    // "4 + 5 + Rocketry 3 = 12 (needed 6)"
+    pub fn breakdown(&self) -> String {
+        let mut text = format!("{} + {} + {} {}", self.dice.0, self.dice.1, self.skill_name, self.skill_value);
+        for modifier in &self.modifiers {
+            text += &format!(" {:+} {}", modifier.value, modifier.source);
+        }
+        text + &format!(" = {} (needed {})", self.total(), self.target)
+    }
+}
+
+impl fmt::Display for ChallengeResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (die1, die2) = self.dice;
+        write!(f, "{} check: rolled {} + {}, skill {}", self.skill_name, die1, die2, self.skill_value)?;
+        for modifier in &self.modifiers {
+            write!(f, ", {} {:+}", modifier.source, modifier.value)?;
+        }
+        write!(f, " = {} against {}. ", self.total(), self.target)?;
+        match (self.critical, self.success) {
+            (true, true) => write!(f, "Critical roll! Automatic success."),
+            (true, false) => write!(f, "Critical roll! Automatic failure."),
+            (false, true) => write!(f, "Success!"),
+            (false, false) => write!(f, "Failure."),
+        }
+    }
+}
+
+// What the frontend should show for the current dialogue
+#[derive(Clone, Debug, PartialEq)]
+pub struct View {
+    pub location_id: String,
+    pub dialogue_id: String,
+    pub speaker: String,
+    pub intro: String,
+    pub options: Vec<OptionView>,
+    pub passive_checks: Vec<PassiveView>,
+    pub announcements: Vec<Announcement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptionView {
+    pub index: usize, // Position in the dialogue's options, which is what `Engine::choose` takes
+    pub description: String,
+    pub check: Option<CheckPreview>, // For challenges, the skill, difficulty and chance of success
+    pub locked: Option<String>,      // Why a check can't be tried right now
+}
+
+impl OptionView {
+    // What the option's button says, with the check in front for challenges and the reason for a lock after
+    pub fn label(&self) -> String {
+        let label = match &self.check {
+            Some(check) => format!("{} {}", check, self.description),
+            None => self.description.clone(),
+        };
+        match &self.locked {
+            Some(reason) => format!("🔒 {} ({})", label, reason),
+            None => label,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassiveView {
+    pub skill: String,
+    pub speaker: String,
+    pub text: String,
+    pub success: bool,
+}
+
+// A scheduled event's text, for a player who was there when it happened
+#[derive(Clone, Debug, PartialEq)]
+pub struct Announcement {
+    pub speaker: Option<String>, // None for the narrator
+    pub text: String,
+}
+
+// The game's rules, with no window attached. Frontends read `current_view` and call `choose`.
+pub struct Engine {
+    pub content: Content,
+    pub player: Player,
+    pub world: WorldState,
+    pub location_id: String,
+    pub dialogue_id: Option<String>, // None if not in a dialogue
+    pub time: Time,
+    pub history: History,
+    pub interjections: Vec<PassiveView>, // What the passive checks said on entering the current dialogue
+    pub announcements: Vec<Announcement>, // Scheduled events since the player last chose or travelled
+    dice: Box<dyn DiceRoller>,
+}
+
+impl Engine {
+    pub fn new(content: Content, player: Player) -> Self {
+        Self {
+            content,
+            player,
+            world: WorldState::default(),
+            location_id: START_LOCATION.to_string(),
+            dialogue_id: Some(START_DIALOGUE.to_string()),
+            time: START_TIME,
+            history: History::default(),
+            interjections: vec![],
+            announcements: vec![],
+            dice: Box::new(SeededDice::from_entropy()),
+        }
+    }
+
+    // Replaces the dice, e.g. with a fixed seed or scripted rolls in tests
+    pub fn with_dice(mut self, dice: impl DiceRoller + 'static) -> Self {
+        self.dice = Box::new(dice);
+        self
+    }
+
+    pub fn dice_state(&self) -> DiceState {
+        self.dice.state()
+    }
+
+    pub fn set_dice_state(&mut self, state: DiceState) {
+        self.dice = state.into_roller();
+    }
+
+    // Starts over in the Vestibule with the same character
+    pub fn new_game(&mut self) -> Vec<GameEvent> {
+        self.start_at(START_LOCATION, START_DIALOGUE)
+    }
+
+    // Starts over from any dialogue, as a new game would from the Vestibule; the editor's play-tests start here
+    pub fn start_at(&mut self, location_id: &str, dialogue_id: &str) -> Vec<GameEvent> {
+        self.world = WorldState::default();
+        self.player.items.clear();
+        self.player.temporary_modifiers.clear();
+        self.location_id = location_id.to_string();
+        self.dialogue_id = Some(dialogue_id.to_string());
+        self.time = START_TIME;
+        self.history.clear();
+        self.interjections.clear();
+        self.announcements.clear();
+        self.enter()
+    }
+
+    // Marks the current dialogue as visited, awarding its XP the first time, then applies its effects.
+    // A move among them arrives at its target and enters that in turn.
+    pub fn enter(&mut self) -> Vec<GameEvent> {
+        let mut events = vec![];
+        for _ in 0..MAX_MOVES_PER_ENTRY {
+            let Some(target) = self.enter_once(&mut events) else { break };
+            let (location_id, dialogue_id) = location_target(&target);
+            self.arrive(location_id, dialogue_id, &mut events);
+        }
+        events
+    }
+
+    // Goes to an option's target, as `resolve_target` reads it from the current location, and enters it.
+    // Every move between dialogues comes through here or `arrive`, whether or not the location changes.
+    pub fn transition(&mut self, target: &str) -> Vec<GameEvent> {
+        let (location_id, dialogue_id) = resolve_target(&self.content.locations, &self.location_id, target);
+        let mut events = vec![];
+        self.arrive(location_id, dialogue_id, &mut events);
+        events.extend(self.enter());
+        events
+    }
+
+    // Walks through one of the current location's exits to that location's entry dialogue, which takes the
+    // rules' travel time. A locked exit says why instead, and shows its locked dialogue if it has one.
+    // Anywhere an exit doesn't lead from here does nothing.
+    pub fn travel(&mut self, location_id: &str) -> Vec<GameEvent> {
+        let Some(room) = self.map().room(location_id).filter(|room| room.adjacent).cloned() else { return vec![] };
+        self.announcements.clear();
+        if let Some(reason) = room.locked {
+            let mut events = vec![GameEvent::ExitLocked { location: room.name, reason }];
+            let exit = self.content.locations[&self.location_id].exits.iter().find(|exit| exit.to == location_id);
+            if let Some(target) = exit.and_then(|exit| exit.locked_dialogue.clone()) {
+                events.extend(self.transition(&target));
+            }
+            return events;
+        }
+        self.history.push(HistoryEntry::Choice(format!("Go to {}", room.name)));
+
+        let mut events = vec![];
+        if self.content.rules.travel_time.minutes > 0 {
+            self.pass_time(self.content.rules.travel_time, &mut events);
+        }
+        self.arrive(location_id.to_string(), ENTRY_DIALOGUE.to_string(), &mut events);
+        events.extend(self.enter());
+        events
+    }
+
+    pub fn map(&self) -> Map {
+        Map::new(&self.content.locations, &self.world, START_LOCATION, &self.location_id, |condition| self.holds(condition))
+    }
+
+    // Puts the player in a dialogue without entering it yet. A target that doesn't exist leaves them
+    // on the error node, see `current_view`. A location without an entry dialogue leaves them out of any
+    // dialogue, in the room itself, see `room_view`.
+    fn arrive(&mut self, location_id: String, dialogue_id: String, events: &mut Vec<GameEvent>) {
+        if location_id != self.location_id {
+            self.location_id = location_id.clone();
+            events.push(GameEvent::LocationChanged(location_id.clone()));
+        }
+        let location = self.content.locations.get(&location_id);
+        if dialogue_id == ENTRY_DIALOGUE && location.is_some_and(|location| !location.dialogues.contains_key(ENTRY_DIALOGUE)) {
+            self.dialogue_id = None;
+            self.world.mark_arrived(&location_id);
+            return;
+        }
+        self.dialogue_id = Some(dialogue_id.clone());
+        events.push(GameEvent::DialogueEntered(dialogue_id));
+        // Time only moves forward when a new dialogue is entered
+        if let Some(duration) = self.current_dialogue().and_then(|dialogue| dialogue.time) {
+            self.pass_time(duration, events);
+        }
+    }
+
+    // Returns where the dialogue's effects move the player, if anywhere
+    fn enter_once(&mut self, events: &mut Vec<GameEvent>) -> Option<String> {
+        let dialogue_id = self.dialogue_id.clone()?;
+        let dialogue = self.current_dialogue()?;
+        let (xp_reward, effects) = (dialogue.xp_reward, dialogue.effects.clone());
+
+        if self.world.mark_visited(&self.location_id, &dialogue_id) {
+            if let Some(xp) = xp_reward {
+                self.grant_xp(xp, events);
+            }
+        }
+        let move_to = self.apply_effects(&effects, events);
+        // After the effects, so the passive checks see any skill they changed
+        self.record_entry();
+        move_to
+    }
+
+    fn grant_xp(&mut self, xp: i32, events: &mut Vec<GameEvent>) {
+        events.push(GameEvent::XpGained(xp));
+        let gained = self.player.add_xp(xp);
+        if gained > 0 {
+            events.push(GameEvent::SkillPointsGained { gained, total: self.player.skill_points });
+        }
+    }
+
+    // Moves the clock on, wears off temporary modifiers whose time is up, then fires the scheduled events
+    // it passed. Their effects apply wherever the player is; their text only reaches the right location.
+    fn pass_time(&mut self, duration: Duration, events: &mut Vec<GameEvent>) {
+        let from = self.time;
+        self.time += duration;
+        events.push(GameEvent::TimePassed(duration));
+        for modifier in &mut self.player.temporary_modifiers {
+            modifier.remaining = modifier.remaining.saturating_sub(duration);
+        }
+        let (expired, active) = self.player.temporary_modifiers.drain(..).partition(|modifier| modifier.remaining.is_zero());
+        self.player.temporary_modifiers = active;
+        for TemporaryModifier { skill, source, .. } in expired {
+            let skill_name = self.content.skills.name(&skill).to_string();
+            events.push(GameEvent::ModifierExpired { skill, skill_name, source });
+        }
+
+        let due: Vec<_> = self.content.schedule.due(from, self.time).into_iter().cloned().collect();
+        for event in due {
+            // The schedule can't move the player or the clock, so there's never a move to make
+            self.apply_effects(&event.effects, events);
+            let Some(text) = event.text.filter(|_| event.location.as_ref().is_none_or(|location| *location == self.location_id)) else {
+                continue;
+            };
+            self.history.push(HistoryEntry::Event { speaker: event.speaker.clone(), text: text.clone() });
+            self.announcements.push(Announcement { speaker: event.speaker, text });
+        }
+    }
+
+    // Applies effects in order. A move is held back until the rest are done and returned for the caller to make.
+    fn apply_effects(&mut self, effects: &[Effect], events: &mut Vec<GameEvent>) -> Option<String> {
+        let mut move_to = None;
+        for effect in effects {
+            match effect {
+                Effect::AddItem(item) => {
+                    self.player.items.push(item.clone());
+                    events.push(GameEvent::ItemPickedUp(item.clone()));
+                }
+                Effect::RemoveItem(item) => {
+                    if let Some(position) = self.player.items.iter().position(|carried| carried == item) {
+                        self.player.items.remove(position);
+                        events.push(GameEvent::ItemRemoved(item.clone()));
+                    }
+                }
+                Effect::SetFlag(flag) => {
+                    self.world.set_flag(flag);
+                    events.push(GameEvent::FlagSet(flag.clone()));
+                }
+                Effect::ClearFlag(flag) => {
+                    if self.world.flags.remove(flag) {
+                        events.push(GameEvent::FlagCleared(flag.clone()));
+                    }
+                }
+                Effect::GrantXp(xp) => self.grant_xp(*xp, events),
+                Effect::ModifySkill { skill, amount, duration, source } => {
+                    self.player.temporary_modifiers.push(TemporaryModifier {
+                        skill: skill.clone(),
+                        amount: *amount,
+                        source: source.clone(),
+                        remaining: *duration,
+                    });
+                    events.push(GameEvent::SkillModified {
+                        skill: skill.clone(),
+                        skill_name: self.content.skills.name(skill).to_string(),
+                        amount: *amount,
+                        duration: *duration,
+                        source: source.clone(),
+                    });
+                }
+                Effect::AdvanceTime(duration) => self.pass_time(*duration, events),
+                Effect::MoveTo(target) => move_to = Some(target.clone()),
+            }
+        }
+        move_to
+    }
+
+    pub fn current_dialogue(&self) -> Option<&Dialogue> {
+        self.content.dialogue(&self.location_id, self.dialogue_id.as_deref()?)
+    }
+
+    pub fn current_view(&self) -> Option<View> {
+        let Some(dialogue_id) = self.dialogue_id.as_deref() else { return self.room_view() };
+        let Some(dialogue) = self.current_dialogue() else { return Some(self.error_view(dialogue_id)) };
+
+        let options = dialogue
+            .options
+            .iter()
+            .enumerate()
+            .filter(|(i, option)| self.is_available(dialogue_id, *i, option))
+            .map(|(index, option)| OptionView {
+                index,
+                description: option.description.clone(),
+                check: self.preview_check(option),
+                locked: self.check_lock(dialogue_id, index, option),
+            })
+            .collect();
+
+        Some(View {
+            location_id: self.location_id.clone(),
+            dialogue_id: dialogue_id.to_string(),
+            speaker: dialogue.speaker.clone(),
+            intro: dialogue.intro.clone(),
+            options,
+            passive_checks: self.interjections.clone(),
+            announcements: self.announcements.clone(),
+        })
+    }
+
+    // Whether the player is on the error node rather than in a dialogue or a room
+    pub fn on_error_node(&self) -> bool {
+        self.dialogue_id.is_some() && self.current_dialogue().is_none()
+    }
+
+    // A location with nothing to say yet: its name, and an option for each exit that goes through it as
+    // the map would. Its options' indices are the exits', see `choose`.
+    fn room_view(&self) -> Option<View> {
+        let location = self.content.locations.get(&self.location_id)?;
+        let options = location
+            .exits
+            .iter()
+            .enumerate()
+            .map(|(index, exit)| {
+                let name = self.content.locations.get(&exit.to).map_or(exit.to.as_str(), |location| location.name.as_str());
+                // Locked doors can still be tried, to hear why they won't open
+                let lock = if self.holds(&exit.requires) { "" } else { "🔒 " };
+                OptionView { index, description: format!("{}Go to {}", lock, name), check: None, locked: None }
+            })
+            .collect();
+        Some(View {
+            location_id: self.location_id.clone(),
+            dialogue_id: String::new(),
+            speaker: String::new(),
+            intro: location.name.clone(),
+            options,
+            passive_checks: vec![],
+            announcements: self.announcements.clone(),
+        })
+    }
+
+    // Shown when content sends the player somewhere that doesn't exist, rather than an empty panel.
+    // Its one option goes back to the location's entry dialogue, see `choose`.
+    fn error_view(&self, dialogue_id: &str) -> View {
+        let intro = if self.content.locations.contains_key(&self.location_id) {
+            format!("There's no dialogue \"{}\" in {}.", dialogue_id, self.location_id)
+        } else {
+            format!("There's no location \"{}\".", self.location_id)
+        };
+        View {
+            location_id: self.location_id.clone(),
+            dialogue_id: dialogue_id.to_string(),
+            speaker: "Error".to_string(),
+            intro,
+            options: vec![OptionView { index: 0, description: "Back to the start".to_string(), check: None, locked: None }],
+            passive_checks: vec![],
+            announcements: vec![],
+        }
+    }
+
+    // Rolls the dialogue's passive checks as it's entered. Each speaks up once, the first time it has
+    // something to say, unless it's repeatable; one without text for the outcome stays quiet and can try again.
+    fn passive_checks(&mut self, dialogue_id: &str, dialogue: &Dialogue) -> Vec<PassiveView> {
+        let mut interjections = vec![];
+        for (i, check) in dialogue.passive_check.iter().enumerate() {
+            let key = passive_key(dialogue_id, i);
+            if !self.holds(&check.visible_when) || (!check.repeatable && self.world.has_heard(&self.location_id, &key)) {
+                continue;
+            }
+            let success = self.content.rules.passive_succeeds(self.player.skill(&self.content.skills, &check.skill), check.target);
+            let Some(text) = (if success { &check.success_text } else { &check.failure_text }) else { continue };
+            self.world.mark_heard(&self.location_id, key);
+            interjections.push(PassiveView {
+                skill: check.skill.clone(),
+                speaker: check.speaker.clone().unwrap_or_else(|| self.content.skills.name(&check.skill).to_string()),
+                text: text.clone(),
+                success,
+            });
+        }
+        interjections
+    }
+
+    // Writes the dialogue the player is in, and what the passive checks said about it, into the history
+    fn record_entry(&mut self) {
+        let Some(dialogue_id) = self.dialogue_id.clone() else { return };
+        let Some(dialogue) = self.current_dialogue().cloned() else { return };
+        self.interjections = self.passive_checks(&dialogue_id, &dialogue);
+        self.history.push(HistoryEntry::Dialogue {
+            location_id: self.location_id.clone(),
+            dialogue_id,
+            speaker: dialogue.speaker,
+            intro: dialogue.intro,
+        });
+        for PassiveView { skill, speaker, text, success } in self.interjections.clone() {
+            self.history.push(HistoryEntry::Passive { skill, speaker, text, success });
+        }
+    }
+
+    fn preview_check(&self, option: &DialogueOption) -> Option<CheckPreview> {
+        let (Some(skill), Some(target)) = (&option.challenge_attribute, option.challenge_number) else { return None };
+        let chance = self.content.rules.success_chance(self.player.skill(&self.content.skills, skill), target);
+        Some(CheckPreview { skill: skill.clone(), skill_name: self.content.skills.name(skill).to_string(), target, chance })
+    }
+
+    // Why a check option can't be tried again, if it can't: red checks after any attempt,
+    // white ones after a failure until the skill is higher than it was
+    fn check_lock(&self, dialogue_id: &str, index: usize, option: &DialogueOption) -> Option<String> {
+        let skill = option.challenge_attribute.as_ref().filter(|_| option.challenge_number.is_some())?;
+        let attempted_at = self.world.check_attempt(&self.location_id, &option_key(dialogue_id, index, option))?;
+        match option.check_kind {
+            CheckKind::Red => Some("red check, already attempted".to_string()),
+            CheckKind::White if self.player.skill(&self.content.skills, skill) <= attempted_at => {
+                Some(format!("failed, raise {} to retry", self.content.skills.name(skill)))
+            }
+            CheckKind::White => None,
+        }
+    }
+
+    // Whether a `visible_when` holds right now; no condition always does
+    pub fn holds(&self, condition: &Option<Condition>) -> bool {
+        let context = Context {
+            player: &self.player,
+            skills: &self.content.skills,
+            world: &self.world, location_id: &self.location_id, time: &self.time };
+        condition.as_ref().is_none_or(|condition| condition.holds(&context))
+    }
+
+    // Picked-up options are gone for good, and options wait for their own `visible_when`
+    // and that of the dialogue they lead to
+    fn is_available(&self, dialogue_id: &str, index: usize, option: &DialogueOption) -> bool {
+        let target = option.success_dialogue.as_deref().and_then(|target| self.content.target_dialogue(&self.location_id, target));
+        !self.world.is_consumed(&self.location_id, &option_key(dialogue_id, index, option))
+            && self.holds(&option.visible_when)
+            && target.is_none_or(|target| self.holds(&target.visible_when))
+    }
+
+    // Takes the option at `index` in the current dialogue, or the exit at `index` when the player is in a room
+    // without one. Options that aren't available do nothing.
+    pub fn choose(&mut self, index: usize) -> Vec<GameEvent> {
+        let Some(dialogue_id) = self.dialogue_id.clone() else {
+            let exit = self.content.locations.get(&self.location_id).and_then(|location| location.exits.get(index));
+            return match exit.map(|exit| exit.to.clone()) {
+                Some(location_id) => self.travel(&location_id),
+                None => vec![],
+            };
+        };
+        if self.current_dialogue().is_none() {
+            return if index == 0 { self.leave_error_node() } else { vec![] };
+        }
+        let Some(option) = self.current_dialogue().and_then(|dialogue| dialogue.options.get(index)).cloned() else {
+            return vec![];
+        };
+        if !self.is_available(&dialogue_id, index, &option) || self.check_lock(&dialogue_id, index, &option).is_some() {
+            return vec![];
+        }
+
+        self.history.push(HistoryEntry::Choice(option.description.clone()));
+        self.announcements.clear();
+
+        // The roll comes first, so the option's own effects can't change it. Then the pickup and flags,
+        // the effects in the order they're listed, and finally the move or the next dialogue.
+        let mut events = vec![];
+        let success = option.challenge_number.is_some().then(|| {
+            let success = self.challenge(&option, &mut events);
+            self.remember_check(&dialogue_id, index, &option, success);
+            success
+        });
+
+        if let Some(item) = &option.item_to_pickup {
+            self.player.items.push(item.clone());
+            self.world.consume(&self.location_id, option_key(&dialogue_id, index, &option));
+            events.push(GameEvent::ItemPickedUp(item.clone()));
+        }
+        for flag in option.flags.iter().flatten() {
+            self.world.set_flag(flag);
+            events.push(GameEvent::FlagSet(flag.clone()));
+        }
+        if let Some(target) = self.apply_effects(&option.effects, &mut events) {
+            let (location_id, dialogue_id) = location_target(&target);
+            self.arrive(location_id, dialogue_id, &mut events);
+            events.extend(self.enter());
+            return events;
+        }
+
+        let target = if success == Some(false) { &option.failure_dialogue } else { &option.success_dialogue };
+        if let Some(target) = target {
+            events.extend(self.transition(target));
+        }
+        events
+    }
+
+    // The error node's way out: the location's entry dialogue, or the room itself if it has none, or the start
+    // of the game if the location is missing too
+    fn leave_error_node(&mut self) -> Vec<GameEvent> {
+        self.announcements.clear();
+        let target = if self.content.locations.contains_key(&self.location_id) {
+            format!("{}::{}", self.location_id, ENTRY_DIALOGUE)
+        } else {
+            format!("{}::{}", START_LOCATION, START_DIALOGUE)
+        };
+        self.transition(&target)
+    }
+
+    // Red checks are spent by any attempt, white ones by a failure at the skill they were tried with
+    fn remember_check(&mut self, dialogue_id: &str, index: usize, option: &DialogueOption, success: bool) {
+        let key = option_key(dialogue_id, index, option);
+        if success && option.check_kind == CheckKind::White {
+            self.world.forget_check(&self.location_id, &key);
+        } else {
+            let skill_value = option.challenge_attribute.as_deref().map_or(0, |skill| self.player.skill(&self.content.skills, skill));
+            self.world.record_check(&self.location_id, key, skill_value);
+        }
+    }
+
+    // Rolls 2d6 plus the skill against the challenge number, as the content's rules decide
+    fn challenge(&mut self, option: &DialogueOption, events: &mut Vec<GameEvent>) -> bool {
+        let (Some(skill), Some(target)) = (&option.challenge_attribute, option.challenge_number) else { return false };
+
+        let dice = self.dice.roll();
+        let mut result = ChallengeResult {
+            skill: skill.clone(),
+            skill_name: self.content.skills.name(skill).to_string(),
+            dice,
+            skill_value: self.player.base_skill(&self.content.skills, skill),
+            modifiers: self
+                .player
+                .modifiers(skill)
+                .map(|modifier| Modifier { source: modifier.source.clone(), value: modifier.amount })
+                .collect(),
+            target,
+            success: false,
+            critical: false,
+        };
+        let outcome = self.content.rules.resolve(dice, result.bonus(), target);
+        (result.success, result.critical) = (outcome.success, outcome.critical);
+        let success = result.success;
+        self.history.push(HistoryEntry::Check { summary: result.to_string(), success });
+        events.push(GameEvent::Challenge(result));
+        success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialogues::{Exit, Location, PassiveCheck};
+    use crate::dice::ScriptedDice;
+    use crate::rules::Rules;
+    use crate::schedule::{Schedule, ScheduledEvent};
+    use crate::skills::SkillRegistry;
+
+    fn option(description: &str, target: &str) -> DialogueOption {
+        DialogueOption { description: description.to_string(), success_dialogue: Some(target.to_string()), ..Default::default() }
+    }
+
+    fn dialogue(intro: &str, options: Vec<DialogueOption>) -> Dialogue {
+        Dialogue { speaker: "Clock".to_string(), intro: intro.to_string(), options, ..Default::default() }
+    }
+
+    // A Vestibule with a pickup, a flag-gated option, a challenge and a way upstairs
+    fn vestibule(dice: (i32, i32)) -> Engine {
+        let mut vestibule = Location::new("The Vestibule".to_string());
+        vestibule.add_dialogue(
+            "Start".to_string(),
+            dialogue(
+                "The front door swings shut.",
+                vec![
+                    DialogueOption { item_to_pickup: Some("Toy Plane".to_string()), ..option("Pick it up", "Start") },
+                    DialogueOption { flags: Some(vec!["wound".to_string()]), ..option("Wind the clock", "Clock") },
+                    DialogueOption { visible_when: Some("wound".parse().unwrap()), ..option("Listen", "Clock") },
+                    DialogueOption {
+                        challenge_attribute: Some("checkmate".to_string()),
+                        challenge_number: Some(10),
+                        failure_dialogue: Some("Start".to_string()),
+                        ..option("Fix it", "Clock")
+                    },
+                    option("Go upstairs", "FirstFloor"),
+                ],
+            ),
+        );
+        vestibule.add_dialogue(
+            "Clock".to_string(),
+            Dialogue {
+                xp_reward: Some(120),
+                time: Some(Duration::from_minutes(15)),
+                passive_check: vec![PassiveCheck {
+                    skill: "apparatchik".to_string(),
+                    target: 9,
+                    success_text: Some("Regulation time.".to_string()),
+                    failure_text: None,
+                    speaker: None,
+                    visible_when: None,
+                    repeatable: false,
+                }],
+                ..dialogue("Tick.", vec![option("Back", "Start")])
+            },
+        );
+        let mut first_floor = Location::new("First Floor".to_string());
+        first_floor.add_dialogue("Start".to_string(), dialogue("A corridor.", vec![]));
+
+        let content = Content {
+            locations: [("Vestibule".to_string(), vestibule), ("FirstFloor".to_string(), first_floor)].into(),
+            skills: SkillRegistry::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap(),
+            rules: Rules::default(),
+            schedule: Schedule::default(),
+        };
+        let player = Player { skills: [("apparatchik".to_string(), 2)].into(), ..Player::default() };
+        let mut engine = Engine::new(content, player).with_dice(ScriptedDice::new([dice]));
+        engine.new_game();
+        engine
+    }
+
+    fn descriptions(engine: &Engine) -> Vec<String> {
+        engine.current_view().unwrap().options.into_iter().map(|option| option.description).collect()
+    }
+
+    #[test]
+    fn picked_up_items_and_flags_change_the_options() {
+        let mut engine = vestibule((3, 3));
+        assert_eq!(descriptions(&engine), ["Pick it up", "Wind the clock", "Fix it", "Go upstairs"]);
+        // Checkmate 1 needs 9 on the dice
+        assert_eq!(engine.current_view().unwrap().options[2].label(), "[Checkmate – Medium 10] 28% Fix it");
+
+        assert_eq!(engine.choose(0)[0], GameEvent::ItemPickedUp("Toy Plane".to_string()));
+        assert_eq!(engine.player.items, ["Toy Plane"]);
+        assert_eq!(descriptions(&engine), ["Wind the clock", "Fix it", "Go upstairs"]);
+        assert!(engine.choose(0).is_empty(), "a used-up option can't be chosen again");
+
+        engine.choose(1);
+        engine.choose(0);
+        assert!(engine.world.has_flag("wound"));
+        assert_eq!(descriptions(&engine), ["Wind the clock", "Listen", "Fix it", "Go upstairs"]);
+    }
+
+    #[test]
+    fn entering_a_dialogue_advances_time_and_awards_xp_once() {
+        let mut engine = vestibule((3, 3));
+        let events = engine.choose(1);
+        assert!(events.contains(&GameEvent::TimePassed(Duration::from_minutes(15))));
+        assert!(events.contains(&GameEvent::XpGained(120)));
+        assert_eq!(engine.time, Time { day: 1, hour: 3, minute: 45 });
+
+        engine.choose(0);
+        let events = engine.choose(1);
+        assert!(!events.iter().any(|event| matches!(event, GameEvent::XpGained(_))));
+        assert_eq!(engine.player.xp, 20);
+        assert_eq!(engine.player.skill_points, 1);
+    }
+
+    #[test]
+    fn passive_checks_speak_once_unless_repeatable() {
+        // Apparatchik 1 + 2 + 6 meets the passive check's 9, and the skill's name stands in for a speaker
+        let mut engine = vestibule((3, 3));
+        engine.choose(1);
+        let expected = PassiveView {
+            skill: "apparatchik".to_string(),
+            speaker: "Apparatchik".to_string(),
+            text: "Regulation time.".to_string(),
+            success: true,
+        };
+        assert_eq!(engine.current_view().unwrap().passive_checks, std::slice::from_ref(&expected));
+
+        engine.choose(0);
+        engine.choose(1);
+        assert_eq!(engine.current_view().unwrap().passive_checks, []);
+
+        let clock = engine.content.locations.get_mut("Vestibule").unwrap().dialogues.get_mut("Clock").unwrap();
+        clock.passive_check[0].repeatable = true;
+        engine.choose(0);
+        engine.choose(1);
+        assert_eq!(engine.current_view().unwrap().passive_checks, [expected]);
+    }
+
+    #[test]
+    fn history_records_what_was_shown_and_chosen() {
+        let mut engine = vestibule((4, 5));
+        engine.choose(3);
+        let entry = |dialogue_id: &str, intro: &str| HistoryEntry::Dialogue {
+            location_id: "Vestibule".to_string(),
+            dialogue_id: dialogue_id.to_string(),
+            speaker: "Clock".to_string(),
+            intro: intro.to_string(),
+        };
+        assert_eq!(
+            engine.history.entries,
+            [
+                entry("Start", "The front door swings shut."),
+                HistoryEntry::Choice("Fix it".to_string()),
+                HistoryEntry::Check {
+                    summary: "Checkmate check: rolled 4 + 5, skill 1 = 10 against 10. Success!".to_string(),
+                    success: true
+                },
+                entry("Clock", "Tick."),
+                HistoryEntry::Passive {
+                    skill: "apparatchik".to_string(),
+                    speaker: "Apparatchik".to_string(),
+                    text: "Regulation time.".to_string(),
+                    success: true
+                },
+            ]
+        );
+
+        engine.new_game();
+        assert_eq!(engine.history.entries.len(), 1, "a new game starts a new transcript");
+    }
+
+    #[test]
+    fn starting_at_a_dialogue_is_a_new_game_there() {
+        let mut engine = vestibule((3, 3));
+        engine.choose(0);
+        engine.choose(1);
+        assert!(engine.world.has_flag("wound") && engine.time > START_TIME);
+
+        let events = engine.start_at("Vestibule", "Clock");
+        assert_eq!(events[0], GameEvent::XpGained(120), "the world starts over, so the XP comes again");
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("Vestibule", Some("Clock")));
+        assert!(!engine.world.has_flag("wound") && engine.player.items.is_empty());
+        assert_eq!(engine.time, START_TIME);
+        assert_eq!(engine.history.entries.len(), 2, "the clock and what the passive check said");
+    }
+
+    #[test]
+    fn challenges_roll_against_the_skill() {
+        // Checkmate 1 + 9 meets 10
+        let mut engine = vestibule((4, 5));
+        let events = engine.choose(3);
+        let GameEvent::Challenge(result) = &events[0] else { panic!("expected a challenge, got {:?}", events) };
+        assert_eq!((result.total(), result.target, result.success, result.critical), (10, 10, true, false));
+        assert_eq!(result.to_string(), "Checkmate check: rolled 4 + 5, skill 1 = 10 against 10. Success!");
+        assert_eq!(result.breakdown(), "4 + 5 + Checkmate 1 = 10 (needed 10)");
+        assert_eq!(engine.dialogue_id.as_deref(), Some("Clock"));
+
+        // Skills go by the name skills.ron gives them
+        let mut engine = vestibule((4, 4));
+        engine.content.skills.skills.iter_mut().find(|skill| skill.id == "checkmate").unwrap().name = "Chess".to_string();
+        assert!(engine.current_view().unwrap().options[2].label().starts_with("[Chess – Medium 10]"));
+        let events = engine.choose(3);
+        let GameEvent::Challenge(result) = &events[0] else { panic!("expected a challenge, got {:?}", events) };
+        assert_eq!(result.breakdown(), "4 + 4 + Chess 1 = 9 (needed 10)");
+        assert_eq!(engine.dialogue_id.as_deref(), Some("Start"));
+
+        // Double sixes pass and double ones fail whatever the numbers say
+        let mut engine = vestibule((6, 6));
+        engine.player.skills.insert("checkmate".to_string(), -20);
+        engine.choose(3);
+        assert_eq!(engine.dialogue_id.as_deref(), Some("Clock"));
+
+        let mut engine = vestibule((1, 1));
+        engine.player.skills.insert("checkmate".to_string(), 20);
+        let events = engine.choose(3);
+        assert!(matches!(&events[0], GameEvent::Challenge(result) if result.critical && !result.success));
+        assert_eq!(engine.dialogue_id.as_deref(), Some("Start"));
+    }
+
+    #[test]
+    fn failed_checks_lock_until_the_skill_goes_up() {
+        let mut engine = vestibule((4, 4));
+        engine.choose(3);
+        let fix_it = |engine: &Engine| engine.current_view().unwrap().options[2].clone();
+        assert_eq!(fix_it(&engine).locked.as_deref(), Some("failed, raise Checkmate to retry"));
+        assert!(fix_it(&engine).label().starts_with("🔒 [Checkmate"));
+        assert!(engine.choose(3).is_empty(), "a locked check can't be rolled again");
+
+        engine.player.skill_points = 1;
+        engine.player.increase_skill(&engine.content.skills, "checkmate");
+        assert_eq!(fix_it(&engine).locked, None);
+
+        // Red checks are done after one go, even a successful one
+        let mut engine = vestibule((6, 6));
+        let start = engine.content.locations.get_mut("Vestibule").unwrap().dialogues.get_mut("Start").unwrap();
+        start.options[3].check_kind = CheckKind::Red;
+        engine.choose(3);
+        engine.choose(0);
+        assert_eq!(fix_it(&engine).locked.as_deref(), Some("red check, already attempted"));
+    }
+
+    #[test]
+    fn conditions_hide_options_dialogues_and_passive_checks() {
+        let mut engine = vestibule((3, 3));
+        let clock = engine.content.locations.get_mut("Vestibule").unwrap().dialogues.get_mut("Clock").unwrap();
+        clock.visible_when = Some("time < 03:40".parse().unwrap());
+        clock.passive_check[0].visible_when = Some("wound and not item(Toy Plane)".parse().unwrap());
+
+        engine.choose(1);
+        assert_eq!(engine.current_view().unwrap().passive_checks.len(), 1);
+
+        // Back at the start after 03:40, nothing leads to the clock any more
+        engine.choose(0);
+        assert_eq!(descriptions(&engine), ["Pick it up", "Go upstairs"]);
+    }
+
+    #[test]
+    fn effects_apply_in_order_and_wear_off() {
+        let mut engine = vestibule((4, 4));
+        let vestibule = engine.content.locations.get_mut("Vestibule").unwrap();
+        let start = vestibule.dialogues.get_mut("Start").unwrap();
+        start.options[1].effects = vec![
+            Effect::ModifySkill { skill: "checkmate".to_string(), amount: 2, duration: Duration::from_minutes(30), source: "Oil can".to_string() },
+            Effect::AddItem("Key".to_string()),
+            Effect::RemoveItem("Key".to_string()),
+            Effect::GrantXp(30),
+        ];
+        start.options[4].effects = vec![Effect::MoveTo("FirstFloor".to_string())];
+        vestibule.dialogues.get_mut("Clock").unwrap().effects = vec![Effect::ClearFlag("wound".to_string()), Effect::AdvanceTime(Duration::from_minutes(5))];
+
+        let events = engine.choose(1);
+        assert_eq!(
+            events[..5],
+            [
+                GameEvent::FlagSet("wound".to_string()),
+                GameEvent::SkillModified {
+                    skill: "checkmate".to_string(),
+                    skill_name: "Checkmate".to_string(),
+                    amount: 2,
+                    duration: Duration::from_minutes(30),
+                    source: "Oil can".to_string(),
+                },
+                GameEvent::ItemPickedUp("Key".to_string()),
+                GameEvent::ItemRemoved("Key".to_string()),
+                GameEvent::XpGained(30),
+            ]
+        );
+        // Entering the clock clears the flag its own option set and takes 15 + 5 minutes
+        assert!(!engine.world.has_flag("wound"));
+        assert_eq!(engine.time, Time { day: 1, hour: 3, minute: 50 });
+
+        // Checkmate 1 + 8 misses 10 without the oil can
+        engine.choose(0);
+        let events = engine.choose(3);
+        let GameEvent::Challenge(result) = &events[0] else { panic!("expected a challenge, got {:?}", events) };
+        assert_eq!(result.breakdown(), "4 + 4 + Checkmate 1 +2 Oil can = 11 (needed 10)");
+        assert!(events.contains(&GameEvent::ModifierExpired {
+            skill: "checkmate".to_string(),
+            skill_name: "Checkmate".to_string(),
+            source: "Oil can".to_string(),
+        }));
+        assert_eq!(engine.player.skill(&engine.content.skills, "checkmate"), 1);
+
+        // A move replaces the option's own target
+        engine.choose(0);
+        engine.choose(4);
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("FirstFloor", Some("Start")));
+    }
+
+    #[test]
+    fn location_targets_move_the_player() {
+        let mut engine = vestibule((3, 3));
+        assert_eq!(
+            engine.choose(4),
+            [
+                GameEvent::LocationChanged("FirstFloor".to_string()),
+                GameEvent::DialogueEntered("Start".to_string()),
+                GameEvent::TimePassed(Duration::from_minutes(1)),
+            ]
+        );
+        assert_eq!(engine.location_id, "FirstFloor");
+        assert_eq!(engine.current_view().unwrap().intro, "A corridor.");
+
+        // From anywhere but Start too, and a qualified target picks the dialogue to land on
+        let mut engine = vestibule((3, 3));
+        let vestibule = engine.content.locations.get_mut("Vestibule").unwrap();
+        vestibule.dialogues.get_mut("Clock").unwrap().options.extend([option("Upstairs", "FirstFloor"), option("Ring", "FirstFloor::Butler")]);
+        let first_floor = engine.content.locations.get_mut("FirstFloor").unwrap();
+        first_floor.add_dialogue("Butler".to_string(), dialogue("You rang?", vec![option("Downstairs", "Vestibule::Clock")]));
+        engine.transition("Clock");
+        engine.choose(1);
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("FirstFloor", Some("Start")));
+        engine.transition("Vestibule::Clock");
+        engine.choose(2);
+        assert_eq!(engine.current_view().unwrap().intro, "You rang?");
+        engine.choose(0);
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("Vestibule", Some("Clock")));
+    }
+
+    #[test]
+    fn travel_goes_through_exits_and_takes_time() {
+        let mut engine = vestibule((3, 3));
+        assert_eq!(engine.travel("FirstFloor"), []);
+
+        engine.content.locations.get_mut("Vestibule").unwrap().add_exit("FirstFloor".to_string());
+        assert!(engine.map().room("FirstFloor").unwrap().adjacent);
+        assert_eq!(
+            engine.travel("FirstFloor")[..3],
+            [
+                GameEvent::TimePassed(Duration::from_minutes(5)),
+                GameEvent::LocationChanged("FirstFloor".to_string()),
+                GameEvent::DialogueEntered("Start".to_string()),
+            ]
+        );
+        // The walk, then a minute for the corridor's Start
+        assert_eq!(engine.time, Time { day: 1, hour: 3, minute: 36 });
+        assert_eq!(engine.history.entries.iter().filter(|entry| entry.matches("Go to First Floor")).count(), 1);
+
+        let map = engine.map();
+        let first_floor = map.room("FirstFloor").unwrap();
+        assert!(first_floor.current && first_floor.visited);
+        // There's no exit back down
+        assert!(!map.room("Vestibule").unwrap().adjacent);
+    }
+
+    #[test]
+    fn rooms_without_dialogues_show_their_exits() {
+        let mut engine = vestibule((3, 3));
+        let mut landing = Location::new("The Landing".to_string());
+        landing.add_exit("Vestibule".to_string());
+        engine.content.locations.insert("Landing".to_string(), landing);
+        engine.content.locations.get_mut("Vestibule").unwrap().add_exit("Landing".to_string());
+
+        let events = engine.travel("Landing");
+        assert!(events.contains(&GameEvent::LocationChanged("Landing".to_string())));
+        assert!(!engine.on_error_node());
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("Landing", None));
+        let view = engine.current_view().unwrap();
+        assert_eq!(view.intro, "The Landing");
+        assert_eq!(descriptions(&engine), ["Go to The Vestibule"]);
+        assert!(engine.map().room("Landing").unwrap().visited);
+
+        // Its options are its exits
+        engine.choose(0);
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("Vestibule", Some("Start")));
+    }
+
+    #[test]
+    fn the_fourth_floors_locked_doors_can_be_tried() {
+        let content = Content::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap();
+        let player = Player::new(&content.skills);
+        let mut engine = Engine::new(content, player);
+        engine.new_game();
+        engine.travel("FirstFloor");
+        engine.travel("FourthFloor");
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("FourthFloor", None));
+
+        let view = engine.current_view().unwrap();
+        let roof = view.options.iter().find(|option| option.description == "🔒 Go to Rooftop Garden").unwrap();
+        let events = engine.choose(roof.index);
+        assert_eq!(
+            events,
+            [GameEvent::ExitLocked {
+                location: "Rooftop Garden".to_string(),
+                reason: "The roof hatch is rusted shut. Someone who knows how buildings are put together could find the trick to it."
+                    .to_string(),
+            }]
+        );
+        assert_eq!(engine.location_id, "FourthFloor");
+
+        // The letter opens the Administrator's door
+        engine.player.items.push("Administrator's letter".to_string());
+        let office = engine.current_view().unwrap().options.into_iter().find(|option| option.description == "Go to Administrator's Room").unwrap();
+        engine.choose(office.index);
+        assert_eq!(engine.location_id, "AdministratorRoom");
+    }
+
+    #[test]
+    fn locked_exits_say_why_until_they_open() {
+        let mut engine = vestibule((3, 3));
+        let vestibule = engine.content.locations.get_mut("Vestibule").unwrap();
+        vestibule.exits.push(Exit {
+            requires: Some("wound".parse().unwrap()),
+            locked_message: Some("The clock is in the way.".to_string()),
+            locked_dialogue: Some("Clock".to_string()),
+            ..Exit::new("FirstFloor".to_string())
+        });
+        assert_eq!(engine.map().room("FirstFloor").unwrap().locked.as_deref(), Some("The clock is in the way."));
+
+        // The door doesn't open, but the clock has something to say about it
+        let events = engine.travel("FirstFloor");
+        assert_eq!(
+            events[0],
+            GameEvent::ExitLocked { location: "First Floor".to_string(), reason: "The clock is in the way.".to_string() }
+        );
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("Vestibule", Some("Clock")));
+
+        engine.world.set_flag("wound");
+        engine.travel("FirstFloor");
+        assert_eq!(engine.location_id, "FirstFloor");
+    }
+
+    #[test]
+    fn missing_targets_show_an_error_node() {
+        let mut engine = vestibule((3, 3));
+        engine.transition("FirstFloor::Cellar");
+        let view = engine.current_view().unwrap();
+        assert_eq!((view.speaker.as_str(), view.intro.as_str()), ("Error", "There's no dialogue \"Cellar\" in FirstFloor."));
+        assert_eq!(descriptions(&engine), ["Back to the start"]);
+        engine.choose(0);
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("FirstFloor", Some("Start")));
+
+        // With no location to go back to, the way out is the start of the game
+        engine.transition("Attic::Start");
+        assert_eq!(engine.current_view().unwrap().intro, "There's no location \"Attic\".");
+        engine.choose(0);
+        assert_eq!((engine.location_id.as_str(), engine.dialogue_id.as_deref()), ("Vestibule", Some("Start")));
+    }
+
+    #[test]
+    fn scheduled_events_fire_as_the_clock_passes_them() {
+        let mut engine = vestibule((3, 3));
+        engine.content.schedule.events = vec![
+            ScheduledEvent {
+                id: "chime".to_string(),
+                every: Some("1h".parse().unwrap()),
+                location: Some("Vestibule".to_string()),
+                speaker: Some("Clock".to_string()),
+                text: Some("Bong.".to_string()),
+                ..Default::default()
+            },
+            ScheduledEvent {
+                id: "butler".to_string(),
+                at: Some(Time { day: 1, hour: 3, minute: 40 }),
+                location: Some("FirstFloor".to_string()),
+                text: Some("The butler leaves.".to_string()),
+                effects: vec![Effect::SetFlag("butler_gone".to_string())],
+                ..Default::default()
+            },
+        ];
+
+        // 03:30 to 03:45: the butler goes, though nobody here sees it
+        let events = engine.choose(1);
+        assert!(events.contains(&GameEvent::FlagSet("butler_gone".to_string())));
+        assert!(engine.world.has_flag("butler_gone"));
+        assert_eq!(engine.current_view().unwrap().announcements, []);
+
+        // Back to the start, then winding the clock again runs past 04:00
+        engine.choose(0);
+        engine.choose(1);
+        assert_eq!(engine.time, Time { day: 1, hour: 4, minute: 1 });
+        let bong = Announcement { speaker: Some("Clock".to_string()), text: "Bong.".to_string() };
+        assert_eq!(engine.current_view().unwrap().announcements, [bong]);
+        assert!(engine.history.entries.contains(&HistoryEntry::Event { speaker: Some("Clock".to_string()), text: "Bong.".to_string() }));
+
+        // It's said once, and the butler doesn't leave again
+        let events = engine.choose(0);
+        assert!(!events.contains(&GameEvent::FlagSet("butler_gone".to_string())));
+        assert_eq!(engine.current_view().unwrap().announcements, []);
+    }
+}
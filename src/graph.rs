@@ -0,0 +1,343 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::dialogues::{resolve_target, DialogueOption, Location, ENTRY_DIALOGUE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            other => Err(format!("unknown graph format \"{}\" (expected dot or mermaid)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeKind {
+    Plain,
+    Success,
+    Failure,
+    Exit,
+}
+
+struct Node {
+    id: String,
+    label: String,
+}
+
+struct Edge {
+    from: String,
+    to: String,
+    label: String,
+    kind: EdgeKind,
+}
+
+struct Cluster {
+    id: String,
+    label: String,
+    nodes: Vec<Node>,
+}
+
+struct Graph {
+    clusters: Vec<Cluster>,
+    missing: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+// Exports every location, or just `only` and the edges leaving it, as a DOT or Mermaid graph
+pub fn export(locations: &HashMap<String, Location>, only: Option<&str>, format: GraphFormat) -> String {
+    let graph = build(locations, only);
+    match format {
+        GraphFormat::Dot => to_dot(&graph),
+        GraphFormat::Mermaid => to_mermaid(&graph),
+    }
+}
+
+fn sanitize(prefix: &str, text: &str) -> String {
+    let mut id = String::from(prefix);
+    id.extend(text.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }));
+    id
+}
+
+fn node_id(location_id: &str, dialogue_id: &str) -> String {
+    sanitize("n_", &format!("{}__{}", location_id, dialogue_id))
+}
+
+// Stands in for a location that has no Start dialogue to land on
+fn location_node_id(location_id: &str) -> String {
+    sanitize("loc_", location_id)
+}
+
+// Where an option's target lands: a dialogue here, another location's Start, or a "Location::Node"
+fn resolve(locations: &HashMap<String, Location>, location_id: &str, target: &str) -> Option<String> {
+    let (target_location, target_dialogue) = resolve_target(locations, location_id, target);
+    let location = locations.get(&target_location)?;
+    if location.dialogues.contains_key(&target_dialogue) {
+        Some(node_id(&target_location, &target_dialogue))
+    } else if target_dialogue == ENTRY_DIALOGUE && target_location != location_id {
+        Some(entry_node(locations, &target_location))
+    } else {
+        None
+    }
+}
+
+fn entry_node(locations: &HashMap<String, Location>, location_id: &str) -> String {
+    if locations[location_id].dialogues.contains_key(ENTRY_DIALOGUE) {
+        node_id(location_id, ENTRY_DIALOGUE)
+    } else {
+        location_node_id(location_id)
+    }
+}
+
+fn option_label(option: &DialogueOption) -> String {
+    let mut label = option.description.clone();
+    if let Some(condition) = &option.visible_when {
+        label.push_str(&format!("\n[if {}]", condition));
+    }
+    if let Some(item) = &option.item_to_pickup {
+        label.push_str(&format!("\n+item: {}", item));
+    }
+    for flag in option.flags.iter().flatten() {
+        label.push_str(&format!("\n+flag: {}", flag));
+    }
+    for effect in &option.effects {
+        label.push_str(&format!("\n{}", effect));
+    }
+    label
+}
+
+fn build(locations: &HashMap<String, Location>, only: Option<&str>) -> Graph {
+    let mut location_ids: Vec<&String> = locations.keys().filter(|id| only.is_none_or(|only| only == *id)).collect();
+    location_ids.sort();
+
+    let mut graph = Graph { clusters: vec![], missing: vec![], edges: vec![] };
+    let mut missing = BTreeSet::new();
+
+    for location_id in location_ids {
+        let location = &locations[location_id];
+        let mut cluster = Cluster { id: location_node_id(location_id), label: location.name.clone(), nodes: vec![] };
+
+        if !location.dialogues.contains_key("Start") {
+            // Locations without dialogues still need a node for exits and transitions to land on
+            cluster.nodes.push(Node { id: location_node_id(location_id), label: location_id.clone() });
+        }
+
+        let mut dialogue_ids: Vec<&String> = location.dialogues.keys().collect();
+        dialogue_ids.sort_by_key(|id| (id.as_str() != "Start", id.as_str()));
+
+        for dialogue_id in dialogue_ids {
+            let dialogue = &location.dialogues[dialogue_id];
+            let from = node_id(location_id, dialogue_id);
+
+            let mut label = if dialogue_id.is_empty() { "(empty ID)".to_string() } else { dialogue_id.clone() };
+            if !dialogue.speaker.is_empty() {
+                label.push_str(&format!("\n{}", dialogue.speaker));
+            }
+            cluster.nodes.push(Node { id: from.clone(), label });
+
+            for option in &dialogue.options {
+                let description = option_label(option);
+                let check = match (&option.challenge_attribute, option.challenge_number) {
+                    (Some(skill), Some(number)) => Some(format!("{} {}", skill, number)),
+                    (None, Some(number)) => Some(format!("? {}", number)),
+                    _ => None,
+                };
+
+                let mut targets = vec![];
+                match check {
+                    Some(check) => {
+                        if let Some(target) = &option.success_dialogue {
+                            targets.push((target, format!("{}\n✓ {}", description, check), EdgeKind::Success));
+                        }
+                        if let Some(target) = &option.failure_dialogue {
+                            targets.push((target, format!("✗ {}", check), EdgeKind::Failure));
+                        }
+                    }
+                    None => {
+                        if let Some(target) = &option.success_dialogue {
+                            targets.push((target, description, EdgeKind::Plain));
+                        }
+                    }
+                }
+
+                for (target, label, kind) in targets {
+                    let to = resolve(locations, location_id, target).unwrap_or_else(|| {
+                        let id = format!("missing_{}", node_id(location_id, target));
+                        missing.insert((id.clone(), target.clone()));
+                        id
+                    });
+                    graph.edges.push(Edge { from: from.clone(), to, label, kind });
+                }
+            }
+        }
+
+        for exit in &location.exits {
+            let to = if locations.contains_key(&exit.to) {
+                entry_node(locations, &exit.to)
+            } else {
+                let id = format!("missing_{}", node_id(location_id, &exit.to));
+                missing.insert((id.clone(), exit.to.clone()));
+                id
+            };
+            let label = match &exit.requires {
+                Some(condition) => format!("exit\n[if {}]", condition),
+                None => "exit".to_string(),
+            };
+            graph.edges.push(Edge { from: entry_node(locations, location_id), to, label, kind: EdgeKind::Exit });
+        }
+
+        graph.clusters.push(cluster);
+    }
+
+    graph.missing = missing
+        .into_iter()
+        .map(|(id, target)| Node { id, label: format!("missing: \"{}\"", target) })
+        .collect();
+    graph
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn to_dot(graph: &Graph) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph dialogues {{").unwrap();
+    writeln!(out, "    rankdir=LR;").unwrap();
+    writeln!(out, "    node [shape=box, style=rounded];").unwrap();
+
+    for cluster in &graph.clusters {
+        writeln!(out, "    subgraph cluster_{} {{", cluster.id).unwrap();
+        writeln!(out, "        label=\"{}\";", dot_escape(&cluster.label)).unwrap();
+        for node in &cluster.nodes {
+            writeln!(out, "        {} [label=\"{}\"];", node.id, dot_escape(&node.label)).unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+    }
+
+    for node in &graph.missing {
+        writeln!(out, "    {} [label=\"{}\", color=red, fontcolor=red];", node.id, dot_escape(&node.label)).unwrap();
+    }
+
+    for edge in &graph.edges {
+        let style = match edge.kind {
+            EdgeKind::Plain => "",
+            EdgeKind::Success => ", color=darkgreen, fontcolor=darkgreen",
+            EdgeKind::Failure => ", color=red, fontcolor=red, style=dashed",
+            EdgeKind::Exit => ", color=gray, fontcolor=gray, style=dotted",
+        };
+        writeln!(out, "    {} -> {} [label=\"{}\"{}];", edge.from, edge.to, dot_escape(&edge.label), style).unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br/>")
+}
+
+fn to_mermaid(graph: &Graph) -> String {
+    let mut out = String::new();
+    writeln!(out, "flowchart LR").unwrap();
+
+    for cluster in &graph.clusters {
+        writeln!(out, "    subgraph cluster_{} [\"{}\"]", cluster.id, mermaid_escape(&cluster.label)).unwrap();
+        for node in &cluster.nodes {
+            writeln!(out, "        {}[\"{}\"]", node.id, mermaid_escape(&node.label)).unwrap();
+        }
+        writeln!(out, "    end").unwrap();
+    }
+
+    for node in &graph.missing {
+        writeln!(out, "    {}[\"{}\"]", node.id, mermaid_escape(&node.label)).unwrap();
+        writeln!(out, "    style {} stroke:red,color:red", node.id).unwrap();
+    }
+
+    for (i, edge) in graph.edges.iter().enumerate() {
+        let arrow = match edge.kind {
+            EdgeKind::Plain | EdgeKind::Success => "-->",
+            EdgeKind::Failure | EdgeKind::Exit => "-.->",
+        };
+        writeln!(out, "    {} {}|\"{}\"| {}", edge.from, arrow, mermaid_escape(&edge.label), edge.to).unwrap();
+        let style = match edge.kind {
+            EdgeKind::Plain => continue,
+            EdgeKind::Success => "stroke:darkgreen,color:darkgreen",
+            EdgeKind::Failure => "stroke:red,color:red",
+            EdgeKind::Exit => "stroke:gray,color:gray",
+        };
+        writeln!(out, "    linkStyle {} {}", i, style).unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialogues::Dialogue;
+
+    fn clock_room() -> HashMap<String, Location> {
+        let mut room = Location::new("Clock Room".to_string());
+        room.add_exit("Hall".to_string());
+        room.add_dialogue(
+            "Start".to_string(),
+            Dialogue {
+                speaker: "Grandfather Clock".to_string(),
+                intro: "Tick.".to_string(),
+                options: vec![
+                    DialogueOption {
+                        description: "Tell me \"stories\"".to_string(),
+                        challenge_attribute: Some("delusion".to_string()),
+                        challenge_number: Some(12),
+                        success_dialogue: Some("Story".to_string()),
+                        failure_dialogue: Some("Mock".to_string()),
+                        ..Default::default()
+                    },
+                    DialogueOption {
+                        description: "Take the gear".to_string(),
+                        success_dialogue: Some("Start".to_string()),
+                        item_to_pickup: Some("Gear".to_string()),
+                        flags: Some(vec!["has_gear".to_string()]),
+                        visible_when: Some("clock_open".parse().unwrap()),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        room.add_dialogue("Story".to_string(), Dialogue { intro: "Once...".to_string(), ..Default::default() });
+        HashMap::from([("ClockRoom".to_string(), room), ("Hall".to_string(), Location::new("Hall".to_string()))])
+    }
+
+    #[test]
+    fn dot_styles_checks_and_annotates_effects() {
+        let dot = export(&clock_room(), Some("ClockRoom"), GraphFormat::Dot);
+
+        assert!(dot.contains("n_ClockRoom__Start -> n_ClockRoom__Story [label=\"Tell me \\\"stories\\\"\\n✓ delusion 12\", color=darkgreen"));
+        assert!(dot.contains("n_ClockRoom__Start -> missing_n_ClockRoom__Mock [label=\"✗ delusion 12\", color=red, fontcolor=red, style=dashed];"));
+        assert!(dot.contains("[label=\"Take the gear\\n[if clock_open]\\n+item: Gear\\n+flag: has_gear\"];"));
+        assert!(dot.contains("n_ClockRoom__Start -> loc_Hall [label=\"exit\", color=gray"));
+        assert!(!dot.contains("subgraph cluster_loc_Hall"));
+    }
+
+    #[test]
+    fn mermaid_numbers_link_styles_by_edge() {
+        let mermaid = export(&clock_room(), None, GraphFormat::Mermaid);
+
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("n_ClockRoom__Start -->|\"Tell me #quot;stories#quot;<br/>✓ delusion 12\"| n_ClockRoom__Story\n    linkStyle 0 stroke:darkgreen"));
+        assert!(mermaid.contains("n_ClockRoom__Start -.->|\"✗ delusion 12\"| missing_n_ClockRoom__Mock\n    linkStyle 1 stroke:red"));
+        assert!(mermaid.contains("subgraph cluster_loc_Hall [\"Hall\"]\n        loc_Hall[\"Hall\"]"));
+    }
+}
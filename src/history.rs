@@ -0,0 +1,115 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+// Where the frontends write `History::to_markdown`
+pub const HISTORY_EXPORT_FILE: &str = "history.md";
+
+// A transcript of the game so far, so text that's scrolled away can be read again.
+// It's saved with the game and starts over with a new one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HistoryEntry {
+    Dialogue { location_id: String, dialogue_id: String, speaker: String, intro: String },
+    Passive { skill: String, speaker: String, text: String, success: bool },
+    Choice(String),
+    Check { summary: String, success: bool }, // The challenge result as the engine describes it
+    Event { speaker: Option<String>, text: String }, // A scheduled event the player was there for
+}
+
+impl HistoryEntry {
+    // Case-insensitive, over everything the entry shows
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let fields: Vec<&str> = match self {
+            HistoryEntry::Dialogue { speaker, intro, .. } => vec![speaker, intro],
+            HistoryEntry::Passive { speaker, text, .. } => vec![speaker, text],
+            HistoryEntry::Choice(description) => vec![description],
+            HistoryEntry::Check { summary, .. } => vec![summary],
+            HistoryEntry::Event { speaker, text } => speaker.iter().chain([text]).map(String::as_str).collect(),
+        };
+        fields.iter().any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+impl History {
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Entries mentioning `query`; all of them for an empty one
+    pub fn search<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a HistoryEntry> {
+        let query = query.trim();
+        self.entries.iter().filter(move |entry| query.is_empty() || entry.matches(query))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut text = "# Shadow Soldiers transcript\n".to_string();
+        for entry in &self.entries {
+            text += "\n";
+            match entry {
+                HistoryEntry::Dialogue { location_id, dialogue_id, speaker, intro } => {
+                    let speaker = if speaker.is_empty() { "Narrator" } else { speaker };
+                    text += &format!("## {}\n\n_{} / {}_\n\n{}\n", speaker, location_id, dialogue_id, intro);
+                }
+                HistoryEntry::Passive { speaker, text: said, .. } => text += &format!("> **{}:** {}\n", speaker, said),
+                HistoryEntry::Choice(description) => text += &format!("**→ {}**\n", description),
+                HistoryEntry::Check { summary, .. } => text += &format!("🎲 _{}_\n", summary),
+                HistoryEntry::Event { speaker: Some(speaker), text: said } => text += &format!("> **{}:** {}\n", speaker, said),
+                HistoryEntry::Event { speaker: None, text: said } => text += &format!("> _{}_\n", said),
+            }
+        }
+        text
+    }
+
+    pub fn export(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_markdown())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_and_exports_the_transcript() {
+        let mut history = History::default();
+        history.push(HistoryEntry::Dialogue {
+            location_id: "Vestibule".to_string(),
+            dialogue_id: "InspectClock".to_string(),
+            speaker: "Grandfather Clock".to_string(),
+            intro: "Tick.".to_string(),
+        });
+        history.push(HistoryEntry::Passive {
+            skill: "gizmo".to_string(),
+            speaker: "Gizmo".to_string(),
+            text: "A gear is missing.".to_string(),
+            success: true,
+        });
+        history.push(HistoryEntry::Choice("Fix it".to_string()));
+        history.push(HistoryEntry::Check { summary: "Checkmate check: rolled 4 + 5, skill 1 = 10 against 10. Success!".to_string(), success: true });
+        history.push(HistoryEntry::Event { speaker: None, text: "The butler leaves.".to_string() });
+
+        assert_eq!(history.search("GEAR").collect::<Vec<_>>(), [&history.entries[1]]);
+        assert_eq!(history.search(" ").count(), 5);
+        assert_eq!(
+            history.to_markdown(),
+            "# Shadow Soldiers transcript\n\
+             \n## Grandfather Clock\n\n_Vestibule / InspectClock_\n\nTick.\n\
+             \n> **Gizmo:** A gear is missing.\n\
+             \n**→ Fix it**\n\
+             \n🎲 _Checkmate check: rolled 4 + 5, skill 1 = 10 against 10. Success!_\n\
+             \n> _The butler leaves._\n"
+        );
+    }
+}
@@ -0,0 +1,17 @@
+pub mod dialogues;
+pub mod validate;
+pub mod graph;
+pub mod world;
+pub mod player;
+pub mod time;
+pub mod engine;
+pub mod save;
+pub mod playthrough;
+pub mod dice;
+pub mod checks;
+pub mod condition;
+pub mod skills;
+pub mod history;
+pub mod rules;
+pub mod map;
+pub mod schedule;
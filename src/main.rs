@@ -1,126 +1,397 @@
 use eframe::{egui, Frame};
 use egui::Id;
-use rand::Rng;
-use std::collections::HashMap;
-use std::collections::HashSet;
-
-mod dialogues;
-use dialogues::{create_locations, Location, Dialogue, DialogueOption, PassiveCheck};
-
-#[derive(Debug)]
-#[derive(PartialEq)]
-struct Time {
-    day: i32,
-    hour: i32,
-    minute: i32,
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod editor;
+use editor::{Editor, EditorAction};
+
+use shadow_soldiers_gui::dice::{take_seed_arg, DiceState};
+use shadow_soldiers_gui::dialogues::{load_locations, Content, DEFAULT_CONTENT_DIR};
+use shadow_soldiers_gui::engine::{ChallengeResult, Engine, GameEvent, PassiveView};
+use shadow_soldiers_gui::graph::{export, GraphFormat};
+use shadow_soldiers_gui::history::{HistoryEntry, HISTORY_EXPORT_FILE};
+use shadow_soldiers_gui::player::{Player, MAX_ATTRIBUTE, MIN_ATTRIBUTE};
+use shadow_soldiers_gui::rules::Rules;
+use shadow_soldiers_gui::schedule::Schedule;
+use shadow_soldiers_gui::skills::SkillRegistry;
+use shadow_soldiers_gui::playthrough::{load_playthroughs, DEFAULT_PLAYTHROUGH_DIR};
+use shadow_soldiers_gui::save::{self, SaveGame, AUTOSAVE_SLOT, SAVE_DIR};
+use shadow_soldiers_gui::validate::{has_errors, validate, Severity};
+
+struct DialogueApp {
+    engine: Engine, // The player, where they are and what they've done; the rules live there too
+    state: GameState,
+    editor: Editor,
+    playtesting: bool, // Started from the editor, so offer a way back to it
+    game_started: bool,
+    save_slot_name: String,
+    save_status: String,
+    shown_check: Option<ShownCheck>, // A skill check being shown before the dialogue it led to
+    toasts: Vec<Toast>,              // What just happened, in the corner for a few seconds
+    show_history: bool,
+    history_search: String,
+    history_status: String,
+    show_map: bool, // Takes the history's place next to the dialogue; only one of them shows at a time
 }
 
-impl Time {
-    fn increase(&mut self, added_minutes: i32) {
-        let days_increased: i32 = added_minutes/1440;
+struct Toast {
+    text: String,
+    shown_at: Option<f64>, // egui time when it was first drawn
+}
 
-        let hours_increased: i32 = (added_minutes - 1440*days_increased)/60;
+struct ShownCheck {
+    result: ChallengeResult,
+    started: Option<f64>, // egui time when it was first drawn
+}
 
-        let minutes_increased: i32 = (added_minutes - 1440*days_increased) - (60*hours_increased);
+// How long the dice tumble, then how long the banner stays before the next dialogue
+const DICE_ROLL_SECONDS: f64 = 0.8;
+const CHECK_BANNER_SECONDS: f64 = 1.7;
+const TOAST_SECONDS: f64 = 3.0;
 
-        self.day += days_increased;
-        self.hour += hours_increased;
-        self.minute += minutes_increased;
+impl DialogueApp {
+    fn new(content: Content) -> Self {
+
+        Self {
+            engine: Engine::new(content.clone(), Player::new(&content.skills)),
+            state: GameState::CharacterCreation, 
+            editor: Editor::new(PathBuf::from(DEFAULT_CONTENT_DIR)),
+            playtesting: false,
+            game_started: false,
+            save_slot_name: String::new(),
+            save_status: String::new(),
+            shown_check: None,
+            toasts: vec![],
+            show_history: false,
+            history_search: String::new(),
+            history_status: String::new(),
+            show_map: false,
+        }
+    }
+
+    // The character built on the creation screen starts over in the Vestibule
+    fn new_game(&mut self) {
+        self.shown_check = None;
+        self.toasts.clear();
+        let events = self.engine.new_game();
+        self.handle_events(&events);
+        self.game_started = true;
+        self.state = GameState::InGame;
+    }
+
+    fn handle_events(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::Challenge(result) => {
+                    self.shown_check = Some(ShownCheck { result: result.clone(), started: None });
+                }
+                // The dialogue and the clock already show these
+                GameEvent::DialogueEntered(_) | GameEvent::TimePassed(_) => {}
+                GameEvent::LocationChanged(_) => {
+                    self.toasts.push(Toast { text: event.to_string(), shown_at: None });
+                    self.autosave();
+                }
+                _ => self.toasts.push(Toast { text: event.to_string(), shown_at: None }),
+            }
+        }
+    }
 
-        if self.minute > 59 {
-            self.minute = self.minute%60;
-            self.hour += 1;
-        } 
+    fn save_to_slot(&mut self, slot: &str) {
+        self.save_status = match save::save(SAVE_DIR, slot, &SaveGame::from_engine(&self.engine)) {
+            Ok(()) => format!("Saved to \"{}\".", slot),
+            Err(err) => format!("Save failed: {}", err),
+        };
+    }
 
-        if self.hour > 23 {
-            self.hour = 0;
-            self.day +=1;
+    fn load_from_slot(&mut self, slot: &str) {
+        // A play-test runs on the editor's unsaved content, but saves go with what's on disk, as autosaves assume
+        let content = if self.playtesting { Content::load(DEFAULT_CONTENT_DIR) } else { Ok(self.engine.content.clone()) };
+        let loaded = content.map_err(|err| err.to_string()).and_then(|content| {
+            let game = save::load(SAVE_DIR, slot, &content).map_err(|err| err.to_string())?;
+            Ok((content, game))
+        });
+        let (content, game) = match loaded {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.save_status = format!("Load failed: {}", err);
+                return;
+            }
         };
 
+        self.engine.content = content;
+        game.restore(&mut self.engine);
+        self.shown_check = None;
+        self.playtesting = false;
+        self.game_started = true;
+        self.save_status = format!("Loaded \"{}\".", slot);
+        self.state = GameState::InGame;
+    }
 
+    fn autosave(&mut self) {
+        // Play-tests run on the editor's unsaved content, which a save couldn't be replayed against
+        if self.playtesting {
+            return;
+        }
+        if let Err(err) = save::save(SAVE_DIR, AUTOSAVE_SLOT, &SaveGame::from_engine(&self.engine)) {
+            eprintln!("Autosave failed: {}", err);
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn display_save_menu(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Save / Load");
+        ui.add_space(10.0);
+
+        if self.game_started {
+            ui.horizontal(|ui| {
+                ui.label("Save name:");
+                ui.text_edit_singleline(&mut self.save_slot_name);
+                if ui.button("Save").clicked() {
+                    let slot = self.save_slot_name.trim().to_string();
+                    self.save_to_slot(&slot);
+                }
+            });
+            ui.add_space(10.0);
+        }
 
-    #[test]
-    fn time_test() {
+        let slots = save::list_slots(SAVE_DIR);
+        if slots.is_empty() {
+            ui.label("No saved games yet.");
+        }
+        for slot in slots {
+            ui.horizontal(|ui| {
+                ui.label(&slot);
+                if ui.button("Load").clicked() {
+                    self.load_from_slot(&slot);
+                }
+                if self.game_started && slot != AUTOSAVE_SLOT && ui.button("Overwrite").clicked() {
+                    self.save_to_slot(&slot);
+                }
+                if ui.button("Delete").clicked() {
+                    self.save_status = match save::delete(SAVE_DIR, &slot) {
+                        Ok(()) => format!("Deleted \"{}\".", slot),
+                        Err(err) => format!("Delete failed: {}", err),
+                    };
+                }
+            });
+        }
+
+        ui.add_space(10.0);
+        ui.label(&self.save_status);
+        ui.add_space(10.0);
+
+        if ui.button("Return").clicked() {
+            self.state = if self.game_started { GameState::InGame } else { GameState::CharacterCreation };
+        }
+    }
+
+    // The dice tumble for a moment, land, and a banner says how it went; then the next dialogue shows
+    fn display_check(&mut self, ui: &mut egui::Ui) {
+        let Some(shown) = &mut self.shown_check else { return };
+        let now = ui.input().time;
+        let elapsed = now - *shown.started.get_or_insert(now);
+        let result = &shown.result;
+        let rolling = elapsed < DICE_ROLL_SECONDS;
+
+        ui.label(egui::RichText::new(format!("{} check", self.engine.content.skills.name(&result.skill))).strong().size(24.0));
+        ui.add_space(20.0);
+
+        ui.horizontal(|ui| {
+            let tumble = (elapsed * 14.0) as i32;
+            let (die1, die2) = if rolling { (tumble % 6 + 1, (tumble * 5 + 3) % 6 + 1) } else { result.dice };
+            draw_die(ui, die1);
+            ui.add_space(10.0);
+            draw_die(ui, die2);
+        });
+        ui.add_space(20.0);
 
-        let mut x = Time {
-            day: 3,
-            hour: 8,
-            minute: 56
+        if rolling {
+            ui.ctx().request_repaint();
+            return;
+        }
+
+        ui.label(egui::RichText::new(result.breakdown()).size(20.0));
+        ui.add_space(10.0);
+
+        let (text, colour) = match (result.critical, result.success) {
+            (true, true) => ("CRITICAL SUCCESS", egui::Color32::from_rgb(30, 140, 60)),
+            (true, false) => ("CRITICAL FAILURE", egui::Color32::from_rgb(170, 30, 30)),
+            (false, true) => ("SUCCESS", egui::Color32::from_rgb(30, 140, 60)),
+            (false, false) => ("FAILURE", egui::Color32::from_rgb(170, 30, 30)),
         };
+        egui::Frame::none().fill(colour).inner_margin(egui::style::Margin::same(10.0)).show(ui, |ui| {
+            ui.label(egui::RichText::new(text).strong().size(28.0).color(egui::Color32::WHITE));
+        });
+        ui.add_space(20.0);
 
-        x.increase(675);
+        if ui.button("Continue").clicked() || elapsed > DICE_ROLL_SECONDS + CHECK_BANNER_SECONDS {
+            self.shown_check = None;
+        } else {
+            ui.ctx().request_repaint();
+        }
+    }
 
-        assert_eq!(x, Time {
-            day: 3,
-            hour: 20,
-            minute: 11,
+    fn display_toasts(&mut self, ctx: &egui::Context) {
+        let now = ctx.input().time;
+        self.toasts.retain(|toast| toast.shown_at.is_none_or(|shown_at| now - shown_at < TOAST_SECONDS));
+        if self.toasts.is_empty() {
+            return;
+        }
 
-            //11 hours 15, which should roll over
-            //so day 3, hour 20, minute 11
+        egui::Area::new("toasts").anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(20.0, -20.0)).show(ctx, |ui| {
+            for toast in &mut self.toasts {
+                toast.shown_at.get_or_insert(now);
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new(&toast.text).size(18.0));
+                });
+            }
         });
+        ctx.request_repaint();
     }
-}
 
-struct DialogueApp {
-    current_text: String,
-    player: Player,
-    locations: HashMap<String, Location>, // All locations in the game
-    current_location_id: String,          // Current location ID
-    current_dialogue_id: Option<String>,  // Current dialogue ID, or None if not in a dialogue
-    state: GameState,
-    previous_dialogue_id: Option<String>,
-    current_time: Time, 
-}
+    // A skill's voice cutting in: its name in its colour, whether the check passed, then what it says
+    fn display_interjection(&self, ui: &mut egui::Ui, check: &PassiveView) {
+        let colour = self.skill_colour(&check.skill);
+        let outcome = if check.success { "[Success]" } else { "[Failure]" };
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new(check.speaker.to_uppercase()).strong().size(18.0).color(colour));
+            ui.label(egui::RichText::new(outcome).size(14.0).color(colour));
+            ui.label(egui::RichText::new(&check.text).size(18.0));
+        });
+    }
 
-impl Default for DialogueApp {
-    fn default() -> Self {
+    // A scheduled event: who says it in bold, or the narrator in italics
+    fn display_announcement(ui: &mut egui::Ui, speaker: Option<&str>, text: &str) {
+        ui.horizontal_wrapped(|ui| match speaker {
+            Some(speaker) => {
+                ui.label(egui::RichText::new(speaker).strong());
+                ui.label(text);
+            }
+            None => {
+                ui.label(egui::RichText::new(text).italics());
+            }
+        });
+    }
 
-        Self {
-            current_text: "Welcome!".to_string(),
-            player: Player {
-                tech: 3,
-                arts: 3,
-                bur: 3, //short for bureaucracy
-                und: 3, //short for underworld
-                checkmate_mod: 0,
-                rocketry_mod: 0,
-                pathology_mod: 0,
-                civic_engineering_mod: 0,
-                apparatchik_mod: 0,
-                quota_mod: 0,
-                robot_mod: 0,
-                dossier_mod: 0,
-                delusion_mod: 0,
-                arts2_mod: 0,
-                arts3_mod: 0,
-                arts4_mod: 0,
-                gunsmoke_mod: 0,
-                prohibition_mod: 0,
-                gizmo_mod: 0,
-                oldtime_religion_mod: 0,
-                items: vec![],
-                xp: 0,
-                skill_points: 0,
-                dialogues_entered: HashSet::new(),
-                flags: HashSet::new(),
-            },
-            locations: create_locations(),
-            current_location_id: "Vestibule".to_string(), // Start in the Vestibule
-            current_dialogue_id: Some("Start".to_string()), // Start with the "Start" dialogue
-            state: GameState::CharacterCreation, 
-            previous_dialogue_id: None,
-            current_time: Time {
-                day: 1,
-                hour: 3,
-                minute: 30,
+    fn skill_colour(&self, skill: &str) -> egui::Color32 {
+        let (r, g, b) = self.engine.content.skills.colour(skill);
+        egui::Color32::from_rgb(r, g, b)
+    }
+
+    // Everything shown so far this game, newest at the bottom, narrowed down by the search box
+    fn display_history(&mut self, ui: &mut egui::Ui) {
+        ui.heading("History");
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.text_edit_singleline(&mut self.history_search);
+            if ui.button("Export to Markdown").clicked() {
+                self.history_status = match self.engine.history.export(HISTORY_EXPORT_FILE) {
+                    Ok(()) => format!("Exported to {}.", HISTORY_EXPORT_FILE),
+                    Err(err) => format!("Export failed: {}", err),
+                };
+            }
+        });
+        ui.label(&self.history_status);
+        ui.separator();
+
+        egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+            for entry in self.engine.history.search(&self.history_search) {
+                match entry {
+                    HistoryEntry::Dialogue { speaker, intro, .. } => {
+                        ui.add_space(10.0);
+                        if !speaker.is_empty() {
+                            ui.label(egui::RichText::new(speaker).strong().size(18.0));
+                        }
+                        ui.label(intro);
+                    }
+                    HistoryEntry::Passive { skill, speaker, text, .. } => {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(egui::RichText::new(speaker.to_uppercase()).strong().color(self.skill_colour(skill)));
+                            ui.label(text);
+                        });
+                    }
+                    HistoryEntry::Event { speaker, text } => Self::display_announcement(ui, speaker.as_deref(), text),
+                    HistoryEntry::Choice(description) => {
+                        ui.label(egui::RichText::new(format!("→ {}", description)).strong());
+                    }
+                    HistoryEntry::Check { summary, success } => {
+                        let colour = if *success { egui::Color32::from_rgb(30, 140, 60) } else { egui::Color32::from_rgb(170, 30, 30) };
+                        ui.label(egui::RichText::new(summary).color(colour));
+                    }
+                }
+            }
+        });
+    }
+
+    // The building's locations and exits. The current room is outlined, rooms not yet visited are greyed out,
+    // and clicking a room an exit leads to from here goes there. Locked doors say why when hovered.
+    fn display_map(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Map");
+        ui.label(format!("Click a neighbouring room to go there. Each move takes {}.", self.engine.content.rules.travel_time));
+        ui.separator();
+
+        let map = self.engine.map();
+        let row_height = 90.0;
+        let room_size = egui::vec2(150.0, 44.0);
+        let width = ui.available_width();
+        let (area, _) = ui.allocate_exact_size(egui::vec2(width, row_height * map.row_count() as f32), egui::Sense::hover());
+
+        // The start is at the bottom and the building goes up from there
+        let centre = |row: usize, column: usize| {
+            let x = area.left() + width * (column as f32 + 1.0) / (map.row_width(row) as f32 + 1.0);
+            let y = area.bottom() - row_height * (row as f32 + 0.5);
+            egui::pos2(x, y)
+        };
+        let room_centre = |location_id: &str| map.room(location_id).map(|room| centre(room.row, room.column));
+
+        let painter = ui.painter().clone();
+        for (from, to) in &map.connections {
+            if let (Some(from), Some(to)) = (room_centre(from), room_centre(to)) {
+                painter.line_segment([from, to], egui::Stroke::new(2.0, egui::Color32::from_gray(90)));
             }
         }
+
+        let mut travel_to = None;
+        for room in &map.rooms {
+            let rect = egui::Rect::from_center_size(centre(room.row, room.column), room_size);
+            let response = ui.interact(rect, Id::new(("map_room", &room.location_id)), egui::Sense::click());
+            let can_travel = room.adjacent && !room.current;
+
+            let fill = if room.visited { egui::Color32::from_gray(55) } else { egui::Color32::from_gray(30) };
+            let text_colour = if room.visited { egui::Color32::WHITE } else { egui::Color32::from_gray(110) };
+            let stroke = if room.current {
+                egui::Stroke::new(3.0, egui::Color32::from_rgb(220, 180, 70))
+            } else if can_travel && room.locked.is_some() {
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(170, 30, 30))
+            } else if can_travel && response.hovered() {
+                egui::Stroke::new(2.0, egui::Color32::WHITE)
+            } else if can_travel {
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(90, 170, 230))
+            } else {
+                egui::Stroke::new(1.0, egui::Color32::from_gray(70))
+            };
+            painter.rect(rect, egui::Rounding::same(6.0), fill, stroke);
+            let name = if room.locked.is_some() { format!("🔒 {}", room.name) } else { room.name.clone() };
+            painter.text(rect.center(), egui::Align2::CENTER_CENTER, name, egui::FontId::proportional(16.0), text_colour);
+
+            let response = match &room.locked {
+                Some(reason) if can_travel => response.on_hover_text(reason),
+                _ => response,
+            };
+            if can_travel && response.clicked() {
+                travel_to = Some(room.location_id.clone());
+            }
+        }
+
+        if let Some(location_id) = travel_to {
+            let events = self.engine.travel(&location_id);
+            self.handle_events(&events);
+        }
+    }
+
+    fn open_editor(&mut self) {
+        self.editor.open();
+        self.state = GameState::Editor;
     }
 }
 
@@ -135,23 +406,38 @@ impl eframe::App for DialogueApp {
                     ui.label("Distribute 12 points among your four stats. Each stat must have between 1 and 6 points.");
 
                     // Sliders for each stat
-                    ui.add(egui::Slider::new(&mut self.player.tech, 1..=6).text("Tech"));
-                    ui.add(egui::Slider::new(&mut self.player.arts, 1..=6).text("Arts"));
-                    ui.add(egui::Slider::new(&mut self.player.bur, 1..=6).text("Bureaucracy"));
-                    ui.add(egui::Slider::new(&mut self.player.und, 1..=6).text("Underworld"));
+                    let registry = &self.engine.content.skills;
+                    for attribute in &registry.attributes {
+                        let value = self.engine.player.attributes.entry(attribute.id.clone()).or_insert(MIN_ATTRIBUTE);
+                        let skills: Vec<String> = registry.skills_of(&attribute.id).map(|skill| skill.label()).collect();
+                        ui.add(egui::Slider::new(value, MIN_ATTRIBUTE..=MAX_ATTRIBUTE).text(attribute.label()))
+                            .on_hover_text(format!("{}\n\nRaises {}", attribute.description, skills.join(", ")));
+                    }
 
                     // Display remaining points
-                    let remaining_points = self.player.remaining_points();
+                    let remaining_points = self.engine.player.remaining_points(registry);
                     ui.label(format!("Remaining points: {}", remaining_points));
 
                     // Disable the "Start Game" button if the allocation is invalid
-                    if self.player.is_valid() {
+                    if self.engine.player.is_valid(registry) {
                         if ui.button("Start Game").clicked() {
-                            self.state = GameState::InGame;
+                            self.new_game();
                         }
                     } else {
                         ui.label("Ensure all stats are between 1 and 6 points, and the total is exactly 12.");
                     }
+
+                    ui.add_space(20.0);
+
+                    if ui.button("Load Game").clicked() {
+                        self.state = GameState::SaveLoad;
+                    }
+
+                    ui.add_space(20.0);
+
+                    if ui.button("Open Content Editor").clicked() {
+                        self.open_editor();
+                    }
                 });
             }
             GameState::InGame => {
@@ -170,164 +456,76 @@ impl eframe::App for DialogueApp {
                             ui.heading("In-Game Dialogue");
                             ui.add_space(20.0);
                     
-                            let current_dialogue_id_clone = self.current_dialogue_id.clone();
-                            let mut new_dialogue_id = None;
-                            let mut new_location_id = None;
-                            let mut options_to_remove = vec![];    // Store which options to remove
-                            let mut items_to_add = vec![];         // Store items to add to inventory after borrow ends
-                            let mut xp_reward = None;              // Store XP reward for later processing
-                            let mut passive_checks = vec![];       // Store passive checks to process later
-
-                            let mut flags_to_add = vec![]; // Store flags to add after immutable borrow ends
-                    
-                            if let Some(current_dialogue_id) = &current_dialogue_id_clone {
-                                // First, get the current dialogue immutably
-                                if let Some(current_dialogue) = self.get_current_dialogue_from_id(current_dialogue_id) {
-
-                                    // Extract time
-                                    let time_forward = current_dialogue.time;
-                            
-                                    // Extract the XP reward
-                                    xp_reward = current_dialogue.xp_reward;
-                            
-                                    // Extract passive checks for later use
-                                    passive_checks = current_dialogue.passive_check.clone();
-
-                                    ui.label(egui::RichText::new(format!("{}", current_dialogue.speaker)).strong().size(24.0));
-
-                                    ui.add_space(20.0);
-
-                                    // Display the dialogue
-                                    // ui.heading(&current_dialogue.intro);
-                                    
-                                    ui.label(egui::RichText::new(format!("{}", &current_dialogue.intro)).size(20.0));
-
-                                    ui.add_space(20.0);
-
-                            
-                                    // Iterate through the dialogue options
-                                    for (i, option) in current_dialogue.options.iter().enumerate() {
-
-                                        let is_visible = match &option.visible_when {
-                                            Some(flag) => self.player.flags.contains(flag),  // Only visible if the flag is set
-                                            None => true,  // Always visible if no flag is required
-                                        };
-
-                                        if is_visible {
-                                            if ui.button(&option.description).clicked() {
-                                                // Clone the item to be picked up to avoid immutable borrow conflicts
-                                                if let Some(item) = &option.item_to_pickup {
-                                                    items_to_add.push(item.clone());  // Add the item for later processing
-                                                    options_to_remove.push(i);        // Mark this option for removal
-                                                }
-
-                                                if let Some(flags) = &option.flags {
-                                                    for flag in flags {
-                                                        flags_to_add.push(flag.clone());  // Collect flags to add later
-                                                    }
-                                                }
-            
-                                                // Handle challenges and dialogue transitions
-                                                if option.challenge_number.is_some() {
-                                                    let success = handle_challenge(&self.player, option);
-                                                    if success {
-                                                        new_dialogue_id = option.success_dialogue.clone();
-                                                    } else {
-                                                        new_dialogue_id = option.failure_dialogue.clone();
-                                                    }
-                                                } else if let Some(success_dialogue) = &option.success_dialogue {
-                                                    // Handle transition to a new location or dialogue
-                                                    if self.locations.contains_key(success_dialogue) {
-                                                        new_location_id = Some(success_dialogue.clone());
-                                                        new_dialogue_id = None;
-                                                    } else {
-                                                        new_dialogue_id = Some(success_dialogue.clone());
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    } 
-
-                                    // Now that the immutable borrow has ended, we can safely add the flags to the player's flags
-                                    for flag in flags_to_add {
-                                        self.player.flags.insert(flag);  // Add each flag to the player's flags
-                                    }
+                            let mut chosen = None;
 
-                                    // Now that the immutable borrow of `current_dialogue` has ended, we can safely mutate `self.player`
-                            
-                                    // Add items to inventory
-                                    for item in items_to_add {
-                                        self.player.items.push(item);
-                                    }
-                            
-                                    // Remove options that have been interacted with
-                                    if !options_to_remove.is_empty() {
-                                        let dialogue = self.get_current_dialogue_from_id_mut(current_dialogue_id).unwrap();
-                                        for &index in options_to_remove.iter().rev() {
-                                            dialogue.options.remove(index);  // Remove the option in reverse order to avoid shifting indices
-                                        }
-                                    }
+                            if self.shown_check.is_some() {
+                                self.display_check(ui);
+                            } else if let Some(view) = self.engine.current_view() {
+                                ui.label(egui::RichText::new(&view.speaker).strong().size(24.0));
 
+                                ui.add_space(20.0);
 
-                            
-                                    // Award XP if this is the first time entering the dialogue
-                                    if !self.player.dialogues_entered.contains(current_dialogue_id) {
-                                        if let Some(xp_amount) = xp_reward {
-                                            self.player.add_xp(xp_amount);
-                                            println!("You gained {} XP!", xp_amount);
-                                        }
-                                        // Mark the dialogue as entered
-                                        self.player.dialogues_entered.insert(current_dialogue_id.clone());
-                                    }
-            
-                                    // Handle passive checks
-                                    for passive_check in passive_checks {
-                                        let player_skill_value = self.get_player_skill(&passive_check.skill) + 6;  // Assume some modifier for skill checks
-                                        let success = player_skill_value >= passive_check.target;
-                                
-                                        if success {
-                                            if let Some(success_text) = &passive_check.success_text {
-                                                ui.heading(&format!("{} says:", passive_check.speaker.clone().unwrap_or("Narrator".to_string())));
-                                                ui.label(success_text);
-                                            }
-                                        } else {
-                                            if let Some(failure_text) = &passive_check.failure_text {
-                                                ui.heading(&format!("{} says:", passive_check.speaker.clone().unwrap_or("Narrator".to_string())));
-                                                ui.label(failure_text);
-                                            }
-                                        }
+                                ui.label(egui::RichText::new(&view.intro).size(20.0));
+
+                                ui.add_space(20.0);
+
+                                for announcement in &view.announcements {
+                                    Self::display_announcement(ui, announcement.speaker.as_deref(), &announcement.text);
+                                    ui.add_space(10.0);
+                                }
+
+                                for check in &view.passive_checks {
+                                    self.display_interjection(ui, check);
+                                    ui.add_space(10.0);
+                                }
+
+                                ui.add_space(10.0);
+
+                                for option in &view.options {
+                                    if ui.add_enabled(option.locked.is_none(), egui::Button::new(option.label())).clicked() {
+                                        chosen = Some(option.index);
                                     }
                                 }
                             }
 
-                            // Handle dialogue or location transition
-                    if let Some(new_id) = new_dialogue_id {
-                        self.current_dialogue_id = Some(new_id.clone());
-
-                        // Now we handle updating time only when a new dialogue is entered
-                        if let Some(new_dialogue) = self.get_current_dialogue_from_id(&new_id) {
-                            if let Some(time_forward) = new_dialogue.time {
-                                self.current_time.increase(time_forward);  // Move time forward only on dialogue change
+                            if let Some(index) = chosen {
+                                let events = self.engine.choose(index);
+                                self.handle_events(&events);
                             }
-                        }
-                    }
-                    if let Some(new_location) = new_location_id {
-                        self.current_location_id = new_location;
-                    }
 
                     ui.add_space(20.0);
             
                     // Add the "View Inventory" button
                     if ui.button("View Inventory").clicked() {
-                        self.previous_dialogue_id = self.current_dialogue_id.clone();
                         self.state = GameState::InventoryView;
                     }
 
                     ui.add_space(20.0);
 
+                    if ui.button("Save / Load").clicked() {
+                        self.state = GameState::SaveLoad;
+                    }
+
+                    ui.add_space(20.0);
+
+                    let history_label = if self.show_history { "Hide History" } else { "History" };
+                    if ui.button(history_label).clicked() {
+                        self.show_history = !self.show_history;
+                        self.show_map = false;
+                    }
+
+                    ui.add_space(20.0);
+
+                    let map_label = if self.show_map { "Hide Map" } else { "Map" };
+                    if ui.button(map_label).clicked() {
+                        self.show_map = !self.show_map;
+                        self.show_history = false;
+                    }
+
+                    ui.add_space(20.0);
+
                     // Add the "Manage Skills" button
                     if ui.button("Manage Skills").clicked() {
-                        self.previous_dialogue_id = self.current_dialogue_id.clone();
                         self.state = GameState::SkillManagement;  // Switch to skill management state
                     }
 
@@ -335,26 +533,76 @@ impl eframe::App for DialogueApp {
 
                     ui.add_space(20.0);
 
-                    ui.label(egui::RichText::new(format!("Day {}, {}:{}", self.current_time.day, self.current_time.hour, self.current_time.minute)).strong().size(24.0));
+                    ui.label(egui::RichText::new(self.engine.time.to_string()).strong().size(24.0));
+                    ui.label(egui::RichText::new(self.engine.time.phase().name()).size(18.0));
 
+                    if self.playtesting {
+                        ui.add_space(20.0);
+
+                        // Unsaved edits are kept, so writers can go back and keep working
+                        if ui.button("Back to Editor").clicked() {
+                            self.state = GameState::Editor;
+                        }
+                    }
 
                         });
 
                 });
+
+                if self.show_history {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        self.display_history(ui);
+                    });
+                } else if self.show_map {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        self.display_map(ui);
+                    });
+                }
             }
             
             
             
 
+            GameState::SaveLoad => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    self.display_save_menu(ui);
+                });
+            }
+
+            GameState::Editor => {
+                match self.editor.show(ctx) {
+                    EditorAction::None => {}
+                    EditorAction::Close => {
+                        // Play-tests may have run on unsaved edits, so start over from what's on disk. Content that
+                        // doesn't load would leave the game running on those edits, so stay in the editor instead.
+                        match Content::load(DEFAULT_CONTENT_DIR) {
+                            Ok(content) => {
+                                self.engine.content = content;
+                                self.playtesting = false;
+                                self.state = GameState::CharacterCreation;
+                            }
+                            Err(err) => self.editor.report(format!("Can't close the editor: failed to load content: {}", err)),
+                        }
+                    }
+                    EditorAction::Playtest { location, dialogue, locations } => {
+                        self.engine.content.locations = locations;
+                        // A fresh character in a new game, so earlier runs don't change how this one goes
+                        self.engine.player = Player::new(&self.engine.content.skills);
+                        self.playtesting = true;
+                        let events = self.engine.start_at(&location, &dialogue);
+                        self.handle_events(&events);
+                        self.state = GameState::InGame;
+                    }
+                }
+            }
+
             GameState::InventoryView => {
                 // Display the player's inventory
                 egui::CentralPanel::default().show(ctx, |ui| {
                     self.display_inventory(ui);
 
-                    // Add a button to return to the previous dialogue/location
+                    // The engine is still in the dialogue the inventory was opened from
                     if ui.button("Return to Game").clicked() {
-                        // Return to the previous dialogue and switch back to InGame state
-                        self.current_dialogue_id = self.previous_dialogue_id.clone();
                         self.state = GameState::InGame;
                     }
                 });
@@ -365,137 +613,23 @@ impl eframe::App for DialogueApp {
                     ui.heading("Skill Management");
                     
                     // Display available skill points
-                    ui.label(format!("Available Skill Points: {}", self.player.skill_points));
+                    ui.label(format!("Available Skill Points: {}", self.engine.player.skill_points));
                     
                     // Display current skills and add buttons to increase skills
-                    ui.horizontal(|ui| {
-                        ui.label(format!("TECH: Checkmate: {}", self.player.checkmate()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.checkmate_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-            
-                    ui.horizontal(|ui| {
-                        ui.label(format!("TECH: Rocketry: {}", self.player.rocketry()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.rocketry_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-            
-                    ui.horizontal(|ui| {
-                        ui.label(format!("TECH: Pathology: {}", self.player.pathology()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.pathology_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-            
-                    ui.horizontal(|ui| {
-                        ui.label(format!("TECH: Civic Engineering: {}", self.player.civic_engineering()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.civic_engineering_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("ARTS: Delusion: {}", self.player.delusion()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.delusion_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("ARTS: Arts2: {}", self.player.arts2()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.arts2_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("ARTS: Arts3: {}", self.player.arts3()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.arts3_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("ARTS: Arts4: {}", self.player.arts4()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.arts4_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("BUR: Quota: {}", self.player.quota()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.quota_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("BUR: Apparatchik: {}", self.player.apparatchik()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.apparatchik_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("BUR: Robot: {}", self.player.robot()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.robot_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("BUR: Dossier: {}", self.player.dossier()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.dossier_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("UND: Gunsmoke: {}", self.player.gunsmoke()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.gunsmoke_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("UND: Prohibition: {}", self.player.prohibition()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.prohibition_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label(format!("UND: Gizmo: {}", self.player.gizmo()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.gizmo_mod += 1;
-                            self.player.skill_points -= 1;
+                    let registry = &self.engine.content.skills;
+                    let player = &mut self.engine.player;
+                    for attribute in &registry.attributes {
+                        for skill in registry.skills_of(&attribute.id) {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{}: {}: {}", attribute.name.to_uppercase(), skill.label(), player.skill(registry, &skill.id)))
+                                    .on_hover_text(&skill.description);
+                                if player.skill_points > 0 && ui.button("Increase").clicked() {
+                                    player.increase_skill(registry, &skill.id);
+                                }
+                            });
                         }
-                    });
+                    }
 
-                    ui.horizontal(|ui| {
-                        ui.label(format!("UND: Oldtime Religion: {}", self.player.oldtime_religion()));
-                        if self.player.skill_points > 0 && ui.button("Increase").clicked() {
-                            self.player.oldtime_religion_mod += 1;
-                            self.player.skill_points -= 1;
-                        }
-                    });
-            
                     // Add a button to return to the game
                     if ui.button("Return to Game").clicked() {
                         self.state = GameState::InGame;
@@ -504,314 +638,191 @@ impl eframe::App for DialogueApp {
             }
             
         }
-    }
-}
 
-
-
-// Add a helper function to get the dialogue based on the cloned dialogue ID
-impl DialogueApp {
-    fn get_current_dialogue_from_id(&self, dialogue_id: &String) -> Option<&Dialogue> {
-        if let Some(location) = self.locations.get(&self.current_location_id) {
-            return location.dialogues.get(dialogue_id);
+        if let GameState::InGame = self.state {
+            self.display_toasts(ctx);
         }
-        None
     }
+}
 
-    fn get_current_dialogue_from_id_mut(&mut self, dialogue_id: &String) -> Option<&mut Dialogue> {
-        // Access the current location and retrieve the dialogue mutably
-        if let Some(location) = self.locations.get_mut(&self.current_location_id) {
-            return location.dialogues.get_mut(dialogue_id);
-        }
-        None
-    }
 
-    // commented because it was never being used, may use it in the future to refactor
-    
-    // fn get_current_location(&self) -> &Location {
-    //     self.locations.get(&self.current_location_id).unwrap()
-    // }
 
+impl DialogueApp {
     fn display_inventory(&self, ui: &mut egui::Ui) {
         ui.heading("Inventory:");
-        if self.player.items.is_empty() {
+        if self.engine.player.items.is_empty() {
             ui.label("You have no items.");
         } else {
-            for item in &self.player.items {
+            for item in &self.engine.player.items {
                 ui.label(item);
             }
         }
     }
-
-    // commented because it was never being called. May use in the future to refactor
-    // fn display_current_dialogue(&mut self, ui: &mut egui::Ui) {
-    //     if let Some(current_dialogue_id) = &self.current_dialogue_id {
-    //         if let Some(current_dialogue) = self.get_current_dialogue_from_id(current_dialogue_id) {
-    //             ui.heading(&format!("{} says:", current_dialogue.speaker));
-    //             ui.heading(&current_dialogue.intro);
-
-    //             let mut new_dialogue_id = None;
-    //             for option in current_dialogue.options.iter() {
-    //                 if ui.button(&option.description).clicked() {
-    //                     new_dialogue_id = option.success_dialogue.clone();
-    //                 }
-    //             }
-
-    //             if let Some(new_id) = new_dialogue_id {
-    //                 self.current_dialogue_id = Some(new_id);
-    //             }
-    //         }
-    //     } else {
-    //         ui.label("No dialogue available.");
-    //     }
-    // }
-
-    fn get_player_skill(&self, skill: &str) -> i32 {
-        match skill {
-            "checkmate" => self.player.checkmate(),
-            "rocketry" => self.player.rocketry(),
-            "pathology" => self.player.pathology(),
-            "civic engineering" => self.player.civic_engineering(),
-            "apparatchik" => self.player.apparatchik(),
-            "quota" => self.player.quota(),
-            "robot" => self.player.robot(),
-            "dossier" => self.player.dossier(),
-            "delusion" => self.player.delusion(),
-            "arts2" => self.player.arts2(),
-            "arts3" => self.player.arts3(),
-            "arts4" => self.player.arts4(),
-            "gunsmoke" => self.player.gunsmoke(),
-            "prohibition" => self.player.prohibition(),
-            "gizmo" => self.player.gizmo(),
-            "oldtime religion" => self.player.oldtime_religion(),
-            _ => 0, // Default to 0 if the skill doesn't exist
-        }
-    }
 }
 
-// Challenge logic
-fn handle_challenge(player: &Player, option: &DialogueOption) -> bool {
-    if let Some(challenge_attribute) = &option.challenge_attribute {
-        if let Some(challenge_number) = option.challenge_number {
-            let attribute_value = match challenge_attribute.as_str() {
-                "checkmate" => player.checkmate(),
-                "rocketry" => player.rocketry(),
-                "pathology" => player.pathology(),
-                "civic engineering" => player.civic_engineering(),
-                "apparatchik" => player.apparatchik(),
-                "quota" => player.quota(),
-                "robot" => player.robot(),
-                "dossier" => player.dossier(),
-                "delusion" => player.delusion(),
-                "arts2" => player.arts2(),
-                "arts3" => player.arts3(),
-                "arts4" => player.arts4(),
-                "gunsmoke" => player.gunsmoke(),
-                "prohibition" => player.prohibition(),
-                "gizmo" => player.gizmo(),
-                "oldtime religion" => player.oldtime_religion(),
-                _ => 0,
-            };
-
-            let (die1, die2) = roll_dice();
-            let roll_sum = die1 + die2;
-
-            println!("You rolled: {} + {} = {}", die1, die2, roll_sum);
-
-            if die1 == 6 && die2 == 6 {
-                println!("Double sixes! Automatic success.");
-                return true;
-            } else if die1 == 1 && die2 == 1 {
-                println!("Double ones! Automatic failure.");
-                return false;
-            }
-
-            let total = roll_sum + attribute_value;
-            if total >= challenge_number {
-                println!("Success! You needed {}, and you got {}.", challenge_number, total);
-                return true;
-            } else {
-                println!("Failure. You needed {}, but you got {}.", challenge_number, total);
-                return false;
-            }
-        }
+fn draw_die(ui: &mut egui::Ui, value: i32) {
+    let size = 56.0;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect(rect, egui::Rounding::same(8.0), egui::Color32::WHITE, egui::Stroke::new(2.0, egui::Color32::DARK_GRAY));
+
+    let pips: &[(f32, f32)] = match value {
+        1 => &[(0.5, 0.5)],
+        2 => &[(0.25, 0.25), (0.75, 0.75)],
+        3 => &[(0.25, 0.25), (0.5, 0.5), (0.75, 0.75)],
+        4 => &[(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)],
+        5 => &[(0.25, 0.25), (0.75, 0.25), (0.5, 0.5), (0.25, 0.75), (0.75, 0.75)],
+        _ => &[(0.25, 0.25), (0.75, 0.25), (0.25, 0.5), (0.75, 0.5), (0.25, 0.75), (0.75, 0.75)],
+    };
+    for &(x, y) in pips {
+        painter.circle_filled(rect.min + egui::vec2(x, y) * size, size * 0.08, egui::Color32::BLACK);
     }
-    false
-}
-
-fn roll_dice() -> (i32, i32) {
-    let mut rng = rand::thread_rng();
-    (rng.gen_range(1..=6), rng.gen_range(1..=6))
 }
 
-struct Player {
-    tech: i32,
-    arts: i32,
-    bur: i32, //short for bureaucracy
-    und: i32, //short for underworld
-    checkmate_mod: i32,
-    rocketry_mod: i32,
-    pathology_mod: i32,
-    civic_engineering_mod: i32,
-    apparatchik_mod: i32,
-    quota_mod: i32,
-    robot_mod: i32,
-    dossier_mod: i32,
-    delusion_mod: i32,
-    arts2_mod: i32,
-    arts3_mod: i32,
-    arts4_mod: i32,
-    gunsmoke_mod: i32,
-    prohibition_mod: i32,
-    gizmo_mod: i32,
-    oldtime_religion_mod: i32,
-    items: Vec<String>,
-    xp: i32,
-    skill_points: i32,
-    dialogues_entered: HashSet<String>,
-    flags: HashSet<String>,
+enum GameState {
+    CharacterCreation,
+    InGame,
+    InventoryView,
+    SkillManagement,
+    SaveLoad,
+    Editor,
 }
 
-impl Player {
-    fn checkmate(&self) -> i32 {
-        self.tech + self.checkmate_mod
-    }
-
-    fn rocketry(&self) -> i32 {
-        self.tech + self.rocketry_mod
-    }
-
-    fn pathology(&self) -> i32 {
-        self.tech + self.pathology_mod
-    }
-
-    fn civic_engineering(&self) -> i32 {
-        self.tech + self.civic_engineering_mod
-    }
-
-    fn apparatchik(&self) -> i32 {
-        self.bur + self.apparatchik_mod
-    }
-
-    fn quota(&self) -> i32 {
-        self.bur + self.quota_mod
-    }
-
-    fn robot(&self) -> i32 {
-        self.bur + self.robot_mod
-    }
-
-    fn dossier(&self) -> i32 {
-        self.bur + self.dossier_mod
-    }
-
-    fn delusion(&self) -> i32 {
-        self.arts + self.delusion_mod
-    }
-
-    fn arts2(&self) -> i32 {
-        self.arts + self.arts2_mod
-    }
-
-    fn arts3(&self) -> i32 {
-        self.arts + self.arts3_mod
-    }
-
-    fn arts4(&self) -> i32 {
-        self.arts + self.arts4_mod
-    }
-
-    fn gunsmoke(&self) -> i32 {
-        self.und + self.gunsmoke_mod
-    }
+// `shadow_soldiers_gui check [content dir]` validates the content and exits nonzero on errors
+fn check_content(content_dir: &str) -> ExitCode {
+    // The rules and events aren't needed to check the locations, but a mistake in them should still show up here
+    let loaded = Rules::load(content_dir)
+        .and_then(|_| SkillRegistry::load(content_dir))
+        .and_then(|skills| Ok((load_locations(content_dir)?, skills)))
+        .and_then(|(locations, skills)| Schedule::load(content_dir, &locations, &skills).map(|_| (locations, skills)));
+    let (locations, skills) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    fn prohibition(&self) -> i32 {
-        self.und + self.prohibition_mod
+    let issues = validate(&locations, &skills);
+    for issue in &issues {
+        println!("{}", issue);
     }
 
-    fn gizmo(&self) -> i32 {
-        self.und + self.gizmo_mod
-    }
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    println!("{} errors, {} warnings", errors, issues.len() - errors);
 
-    fn oldtime_religion(&self) -> i32 {
-        self.und + self.oldtime_religion_mod
+    if has_errors(&issues) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
     }
+}
 
-    fn total_points(&self) -> i32 {
-        self.tech + self.arts + self.bur + self.und
+// `shadow_soldiers_gui graph <dot|mermaid> [location]` prints the dialogue graph for writers to render
+fn print_graph(format: Option<&str>, location: Option<&str>) -> ExitCode {
+    let format: GraphFormat = match format.unwrap_or("dot").parse() {
+        Ok(format) => format,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let locations = match load_locations(DEFAULT_CONTENT_DIR) {
+        Ok(locations) => locations,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(location) = location {
+        if !locations.contains_key(location) {
+            eprintln!("error: no location \"{}\"", location);
+            return ExitCode::FAILURE;
+        }
     }
 
-    fn remaining_points(&self) -> i32 {
-        12 - self.total_points()
-    }
+    print!("{}", export(&locations, location, format));
+    ExitCode::SUCCESS
+}
 
-    fn is_valid(&self) -> bool {
-        self.tech >= 1 && self.arts >= 1 && self.bur >= 1 && self.und >= 1
-            && self.tech <= 6 && self.arts <= 6 && self.bur <= 6 && self.und <= 6
-            && self.total_points() == 12
+// `shadow_soldiers_gui replay [playthrough dir]` runs the scripted playthroughs against the content
+fn replay_playthroughs(dir: &str) -> ExitCode {
+    let content = match Content::load(DEFAULT_CONTENT_DIR) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let playthroughs = match load_playthroughs(dir) {
+        Ok(playthroughs) => playthroughs,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = 0;
+    for (name, playthrough) in &playthroughs {
+        match playthrough.run(&content) {
+            Ok(()) => println!("ok: {}", name),
+            Err(err) => {
+                println!("FAILED: {}: {}", name, err);
+                failed += 1;
+            }
+        }
     }
+    println!("{} passed, {} failed", playthroughs.len() - failed, failed);
 
-    fn add_xp(&mut self, amount: i32) {
-        self.xp += amount;
-
-        // Handle leveling up
-        while self.xp >= 100 {
-            self.xp -= 100; // Reset XP and preserve the overflow
-            self.skill_points += 1; // Award skill points
-            println!("You gained a skill point! You now have {} skill points.", self.skill_points);
-        }
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
     }
 }
 
-impl Default for Player {
-    fn default() -> Self {
-        Self {
-        tech: 1,
-        arts: 1,
-        bur: 1, //short for bureaucracy
-        und: 1, //short for underworld
-        checkmate_mod: 0,
-        rocketry_mod: 0,
-        pathology_mod: 0,
-        civic_engineering_mod: 0,
-        apparatchik_mod: 0,
-        quota_mod: 0,
-        robot_mod: 0,
-        dossier_mod: 0,
-        delusion_mod: 0,
-        arts2_mod: 0,
-        arts3_mod: 0,
-        arts4_mod: 0,
-        gunsmoke_mod: 0,
-        prohibition_mod: 0,
-        gizmo_mod: 0,
-        oldtime_religion_mod: 0,
-        items: vec![],
-        xp: 0,
-        skill_points: 0,
-        dialogues_entered: HashSet::new(),
-        flags: HashSet::new(),
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().collect();
+    let seed = match take_seed_arg(&mut args) {
+        Ok(seed) => seed,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
         }
+    };
+    match args.get(1).map(String::as_str) {
+        Some("check") => return check_content(args.get(2).map_or(DEFAULT_CONTENT_DIR, String::as_str)),
+        Some("graph") => return print_graph(args.get(2).map(String::as_str), args.get(3).map(String::as_str)),
+        Some("replay") => return replay_playthroughs(args.get(2).map_or(DEFAULT_PLAYTHROUGH_DIR, String::as_str)),
+        _ => {}
     }
-}
 
-enum GameState {
-    CharacterCreation,
-    InGame,
-    InventoryView,
-    SkillManagement,
-}
+    let content = match Content::load(DEFAULT_CONTENT_DIR) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Failed to load game content: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
 
-fn main() {
-    
-    let app = DialogueApp::default();
-    let mut native_options = eframe::NativeOptions::default();
-    native_options.fullscreen = true; 
+    let mut app = DialogueApp::new(content);
+    // The same seed plays out the same rolls for the same choices
+    if let Some(seed) = seed {
+        app.engine.set_dice_state(DiceState::Seeded { seed, rolls: 0 });
+    }
+    if args.get(1).map(String::as_str) == Some("edit") {
+        app.open_editor();
+    }
+    let native_options = eframe::NativeOptions {
+        fullscreen: true,
+        ..Default::default()
+    };
 
     eframe::run_native(
         "Dialogue System",  // App name
         native_options,     // Window options
         Box::new(|_cc| Box::new(app)),  // Closure to create the app
     );
-}
 
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::condition::Condition;
+use crate::dialogues::Location;
+use crate::world::WorldState;
+
+// The building as the player sees it: locations joined by their exits, laid out in rows by how many moves
+// each is from the start. Locations no exit leads to from there go in a row of their own at the end.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Map {
+    pub rooms: Vec<Room>,                // By row, then by ID within the row
+    pub connections: Vec<(String, String)>, // Each pair of locations with an exit between them, once
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Room {
+    pub location_id: String,
+    pub name: String,
+    pub row: usize,
+    pub column: usize,
+    pub visited: bool,
+    pub current: bool,
+    pub adjacent: bool, // An exit leads here from the current location, so the player can travel to it
+    pub locked: Option<String>, // Why that exit won't open right now, see `Exit::lock_reason`
+}
+
+impl Map {
+    // `holds` decides the exits' conditions, as `Engine::holds` does
+    pub fn new(
+        locations: &HashMap<String, Location>,
+        world: &WorldState,
+        start: &str,
+        current: &str,
+        holds: impl Fn(&Option<Condition>) -> bool,
+    ) -> Self {
+        // Breadth-first from the start, so each location's row is its distance
+        let mut rows: HashMap<&str, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        if locations.contains_key(start) {
+            rows.insert(start, 0);
+            queue.push_back(start);
+        }
+        while let Some(id) = queue.pop_front() {
+            let row = rows[id];
+            for exit in &locations[id].exits {
+                if locations.contains_key(&exit.to) && !rows.contains_key(exit.to.as_str()) {
+                    rows.insert(&exit.to, row + 1);
+                    queue.push_back(&exit.to);
+                }
+            }
+        }
+        let unreached_row = rows.values().max().map_or(0, |row| row + 1);
+
+        let mut ids: Vec<&String> = locations.keys().collect();
+        ids.sort_by_key(|id| (rows.get(id.as_str()).copied().unwrap_or(unreached_row), id.as_str()));
+
+        let exits_here = locations.get(current).map_or(&[][..], |location| &location.exits[..]);
+        let mut rooms: Vec<Room> = vec![];
+        for id in ids {
+            let row = rows.get(id.as_str()).copied().unwrap_or(unreached_row);
+            let column = rooms.iter().filter(|room| room.row == row).count();
+            let exit = exits_here.iter().find(|exit| exit.to == *id);
+            rooms.push(Room {
+                location_id: id.clone(),
+                name: locations[id].name.clone(),
+                row,
+                column,
+                visited: world.has_visited_location(id),
+                current: id == current,
+                adjacent: exit.is_some(),
+                locked: exit.filter(|exit| !holds(&exit.requires)).map(|exit| exit.lock_reason()),
+            });
+        }
+
+        let mut connections = BTreeSet::new();
+        for (id, location) in locations {
+            for exit in location.exits.iter().filter(|exit| locations.contains_key(&exit.to)) {
+                let pair = if *id < exit.to { (id.clone(), exit.to.clone()) } else { (exit.to.clone(), id.clone()) };
+                connections.insert(pair);
+            }
+        }
+
+        Self { rooms, connections: connections.into_iter().collect() }
+    }
+
+    pub fn room(&self, location_id: &str) -> Option<&Room> {
+        self.rooms.iter().find(|room| room.location_id == location_id)
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rooms.iter().map(|room| room.row + 1).max().unwrap_or(0)
+    }
+
+    // How many rooms share `row`, for spacing them out
+    pub fn row_width(&self, row: usize) -> usize {
+        self.rooms.iter().filter(|room| room.row == row).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rooms_are_laid_out_by_distance_from_the_start() {
+        let mut locations = HashMap::new();
+        for (id, exits) in [("Hall", vec!["Stairs"]), ("Stairs", vec!["Hall", "Attic", "Cellar"]), ("Attic", vec![]), ("Cellar", vec!["Stairs"]), ("Shed", vec!["Hall"])] {
+            let mut location = Location::new(format!("The {}", id));
+            for exit in exits {
+                location.add_exit(exit.to_string());
+            }
+            locations.insert(id.to_string(), location);
+        }
+        locations.get_mut("Stairs").unwrap().exits[2].requires = Some("item(Lamp)".parse().unwrap());
+        let mut world = WorldState::default();
+        world.mark_visited("Hall", "Start");
+        world.mark_visited("Stairs", "Start");
+
+        // Nothing holds, so every exit with a condition is locked
+        let map = Map::new(&locations, &world, "Hall", "Stairs", |condition| condition.is_none());
+        let rooms: Vec<_> = map.rooms.iter().map(|room| (room.location_id.as_str(), room.row, room.column)).collect();
+        assert_eq!(rooms, [("Hall", 0, 0), ("Stairs", 1, 0), ("Attic", 2, 0), ("Cellar", 2, 1), ("Shed", 3, 0)]);
+        assert_eq!((map.row_count(), map.row_width(2)), (4, 2));
+
+        let stairs = map.room("Stairs").unwrap();
+        assert!(stairs.current && stairs.visited && !stairs.adjacent);
+        let attic = map.room("Attic").unwrap();
+        assert!(attic.adjacent && !attic.visited && attic.locked.is_none());
+        assert_eq!(map.room("Cellar").unwrap().locked.as_deref(), Some("needs item(Lamp)"));
+        assert!(!map.room("Shed").unwrap().adjacent);
+
+        // One line per pair, however many exits join them
+        assert_eq!(map.connections.len(), 4);
+        assert!(map.connections.contains(&("Hall".to_string(), "Stairs".to_string())));
+    }
+}
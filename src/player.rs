@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::skills::SkillRegistry;
+use crate::time::Duration;
+
+// Character creation spreads this many points over the attributes, each within the bounds below
+pub const ATTRIBUTE_POINTS: i32 = 12;
+pub const MIN_ATTRIBUTE: i32 = 1;
+pub const MAX_ATTRIBUTE: i32 = 6;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Player {
+    #[serde(default)] // Saves before version 4 had a field per attribute and skill, see `save::migrate_v3`
+    pub attributes: BTreeMap<String, i32>, // By attribute ID; one that isn't set counts as MIN_ATTRIBUTE
+    #[serde(default)]
+    pub skills: BTreeMap<String, i32>, // Points spent on each skill on top of its attribute, by skill ID
+    pub items: Vec<String>,
+    pub xp: i32,
+    pub skill_points: i32,
+    #[serde(default)]
+    pub temporary_modifiers: Vec<TemporaryModifier>,
+}
+
+// A skill bonus or penalty from an effect, until enough game time has passed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemporaryModifier {
+    pub skill: String,
+    pub amount: i32,
+    pub source: String,
+    #[serde(alias = "minutes_left")] // Its name in saves from before durations
+    pub remaining: Duration,
+}
+
+impl Player {
+    // What the character creation screen starts from, before the player moves points around
+    pub fn new(registry: &SkillRegistry) -> Self {
+        let attributes = registry.attributes.iter().map(|attribute| (attribute.id.clone(), 3)).collect();
+        Self { attributes, ..Default::default() }
+    }
+
+    pub fn attribute(&self, attribute_id: &str) -> i32 {
+        self.attributes.get(attribute_id).copied().unwrap_or(MIN_ATTRIBUTE)
+    }
+
+    // Skill value by ID, with any temporary modifiers
+    pub fn skill(&self, registry: &SkillRegistry, skill_id: &str) -> i32 {
+        self.base_skill(registry, skill_id) + self.modifiers(skill_id).map(|modifier| modifier.amount).sum::<i32>()
+    }
+
+    pub fn modifiers<'a>(&'a self, skill_id: &'a str) -> impl Iterator<Item = &'a TemporaryModifier> {
+        self.temporary_modifiers.iter().filter(move |modifier| modifier.skill == skill_id)
+    }
+
+    // The attribute plus the points spent on the skill, without temporary modifiers.
+    // Content is checked against the registry when it loads, so only code can ask for an unknown ID; it gets 0.
+    pub fn base_skill(&self, registry: &SkillRegistry, skill_id: &str) -> i32 {
+        let Some(skill) = registry.skill(skill_id) else { return 0 };
+        self.attribute(&skill.attribute) + self.skills.get(skill_id).copied().unwrap_or(0)
+    }
+
+    pub fn total_points(&self, registry: &SkillRegistry) -> i32 {
+        registry.attributes.iter().map(|attribute| self.attribute(&attribute.id)).sum()
+    }
+
+    pub fn remaining_points(&self, registry: &SkillRegistry) -> i32 {
+        ATTRIBUTE_POINTS - self.total_points(registry)
+    }
+
+    pub fn is_valid(&self, registry: &SkillRegistry) -> bool {
+        registry
+            .attributes
+            .iter()
+            .all(|attribute| (MIN_ATTRIBUTE..=MAX_ATTRIBUTE).contains(&self.attribute(&attribute.id)))
+            && self.total_points(registry) == ATTRIBUTE_POINTS
+    }
+
+    // Spends a skill point on `skill_id`; false if there are none left or the skill doesn't exist
+    pub fn increase_skill(&mut self, registry: &SkillRegistry, skill_id: &str) -> bool {
+        if self.skill_points <= 0 || !registry.contains(skill_id) {
+            return false;
+        }
+        *self.skills.entry(skill_id.to_string()).or_insert(0) += 1;
+        self.skill_points -= 1;
+        true
+    }
+
+    // Returns how many skill points the XP earned
+    pub fn add_xp(&mut self, amount: i32) -> i32 {
+        self.xp += amount;
+
+        // Handle leveling up
+        let mut gained = 0;
+        while self.xp >= 100 {
+            self.xp -= 100; // Reset XP and preserve the overflow
+            self.skill_points += 1; // Award skill points
+            gained += 1;
+        }
+        gained
+    }
+}
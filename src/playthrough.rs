@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::dialogues::{read_ron, Content, ContentError};
+use crate::dice::{DiceState, ScriptedDice};
+use crate::engine::{Engine, GameEvent, OptionView};
+use crate::player::Player;
+use crate::time::Time;
+
+pub const DEFAULT_PLAYTHROUGH_DIR: &str = "playthroughs";
+
+// A scripted run through the content: a character, the options to pick and the dice they'll roll.
+// Anything left out of `expect` isn't checked.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Playthrough {
+    #[serde(default)]
+    pub build: BTreeMap<String, i32>, // Attribute values by ID; any left out start at 3, as in character creation
+    #[serde(default)]
+    pub dice: Vec<(i32, i32)>, // Used in order, one pair per challenge
+    pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub expect: Expected,
+}
+
+// An option's description, or its number as the player would see it (counting from 1, hidden options skipped)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Choice {
+    Number(usize),
+    Text(String),
+}
+
+impl Choice {
+    fn find<'a>(&self, options: &'a [OptionView]) -> Option<&'a OptionView> {
+        match self {
+            Choice::Number(number) => options.get(number.checked_sub(1)?),
+            Choice::Text(text) => options.iter().find(|option| &option.description == text),
+        }
+    }
+}
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Choice::Number(number) => write!(f, "option {}", number),
+            Choice::Text(text) => write!(f, "\"{}\"", text),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Expected {
+    pub location: Option<String>,
+    pub dialogue: Option<String>,
+    pub items: Option<Vec<String>>,
+    pub flags: Option<Vec<String>>,
+    pub xp: Option<i32>,
+    pub skill_points: Option<i32>,
+    pub time: Option<Time>,
+}
+
+#[derive(Debug)]
+pub enum PlaythroughError {
+    NoDialogue { step: usize, location: String, dialogue: Option<String> },
+    UnknownChoice { step: usize, choice: String, dialogue: String, available: Vec<String> },
+    Locked { step: usize, choice: String, reason: String },
+    OutOfDice { step: usize },
+    Mismatch(Vec<String>),
+}
+
+impl fmt::Display for PlaythroughError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlaythroughError::NoDialogue { step, location, dialogue } => {
+                write!(f, "step {}: no dialogue to choose from at {}/{}", step, location, dialogue.as_deref().unwrap_or("-"))
+            }
+            PlaythroughError::UnknownChoice { step, choice, dialogue, available } => write!(
+                f,
+                "step {}: {} isn't an option in {} (options: {})",
+                step,
+                choice,
+                dialogue,
+                available.iter().map(|option| format!("\"{}\"", option)).collect::<Vec<_>>().join(", ")
+            ),
+            PlaythroughError::Locked { step, choice, reason } => write!(f, "step {}: {} is locked: {}", step, choice, reason),
+            PlaythroughError::OutOfDice { step } => write!(f, "step {}: a challenge needed more dice than the script gives", step),
+            PlaythroughError::Mismatch(mismatches) => write!(f, "{}", mismatches.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for PlaythroughError {}
+
+impl Playthrough {
+    pub fn run(&self, content: &Content) -> Result<(), PlaythroughError> {
+        let mut player = Player::new(&content.skills);
+        player.attributes.extend(self.build.clone());
+        let mut engine = Engine::new(content.clone(), player).with_dice(ScriptedDice::new(self.dice.iter().copied()));
+        engine.new_game();
+
+        for (i, choice) in self.choices.iter().enumerate() {
+            let step = i + 1;
+            // The error node has an option of its own, but a playthrough that reaches it has gone wrong
+            let Some(view) = engine.current_view().filter(|_| !engine.on_error_node()) else {
+                return Err(PlaythroughError::NoDialogue {
+                    step,
+                    location: engine.location_id.clone(),
+                    dialogue: engine.dialogue_id.clone(),
+                });
+            };
+            let Some(option) = choice.find(&view.options) else {
+                return Err(PlaythroughError::UnknownChoice {
+                    step,
+                    choice: choice.to_string(),
+                    dialogue: view.dialogue_id,
+                    available: view.options.into_iter().map(|option| option.description).collect(),
+                });
+            };
+            if let Some(reason) = &option.locked {
+                return Err(PlaythroughError::Locked { step, choice: choice.to_string(), reason: reason.clone() });
+            }
+            let index = option.index;
+            let dice_left = dice_left(&engine);
+            let events = engine.choose(index);
+            if dice_left == 0 && events.iter().any(|event| matches!(event, GameEvent::Challenge(_))) {
+                return Err(PlaythroughError::OutOfDice { step });
+            }
+        }
+
+        let mut mismatches = self.expect.compare(&engine);
+        let unused = dice_left(&engine);
+        if unused > 0 {
+            mismatches.push(format!("{} dice rolls were never used", unused));
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(PlaythroughError::Mismatch(mismatches))
+        }
+    }
+}
+
+impl Expected {
+    fn compare(&self, engine: &Engine) -> Vec<String> {
+        let mut mismatches = vec![];
+        let mut check = |field: &str, expected: String, actual: String| {
+            if expected != actual {
+                mismatches.push(format!("{}: expected {}, got {}", field, expected, actual));
+            }
+        };
+
+        if let Some(location) = &self.location {
+            check("location", format!("{:?}", location), format!("{:?}", engine.location_id));
+        }
+        if let Some(dialogue) = &self.dialogue {
+            check("dialogue", format!("{:?}", Some(dialogue)), format!("{:?}", engine.dialogue_id));
+        }
+        if let Some(items) = &self.items {
+            check("items", format!("{:?}", items), format!("{:?}", engine.player.items));
+        }
+        if let Some(flags) = &self.flags {
+            let mut flags = flags.clone();
+            flags.sort();
+            check("flags", format!("{:?}", flags), format!("{:?}", engine.world.flags.iter().collect::<Vec<_>>()));
+        }
+        if let Some(xp) = self.xp {
+            check("xp", xp.to_string(), engine.player.xp.to_string());
+        }
+        if let Some(skill_points) = self.skill_points {
+            check("skill_points", skill_points.to_string(), engine.player.skill_points.to_string());
+        }
+        if let Some(time) = &self.time {
+            check("time", time.to_string(), engine.time.to_string());
+        }
+        mismatches
+    }
+}
+
+fn dice_left(engine: &Engine) -> usize {
+    match engine.dice_state() {
+        DiceState::Scripted { rolls } => rolls.len(),
+        DiceState::Seeded { .. } => usize::MAX,
+    }
+}
+
+// Every <dir>/*.ron as (file stem, playthrough), sorted by name
+pub fn load_playthroughs(dir: impl AsRef<Path>) -> Result<Vec<(String, Playthrough)>, ContentError> {
+    let dir = dir.as_ref();
+    let entries = fs::read_dir(dir).map_err(|source| ContentError::Io { path: dir.to_path_buf(), source })?;
+
+    let mut playthroughs = vec![];
+    for entry in entries {
+        let path = entry.map_err(|source| ContentError::Io { path: dir.to_path_buf(), source })?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else { continue };
+        playthroughs.push((name.to_string(), read_ron(&path)?));
+    }
+    playthroughs.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(playthroughs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content() -> Content {
+        Content::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap()
+    }
+
+    #[test]
+    fn shipped_playthroughs_pass() {
+        let content = content();
+        let playthroughs = load_playthroughs(concat!(env!("CARGO_MANIFEST_DIR"), "/playthroughs")).unwrap();
+        assert!(!playthroughs.is_empty());
+
+        let failures: Vec<String> = playthroughs
+            .iter()
+            .filter_map(|(name, playthrough)| Some(format!("{}: {}", name, playthrough.run(&content).err()?)))
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn reports_what_went_wrong() {
+        let content = content();
+        let script = r#"#![enable(implicit_some)]
+            Playthrough(
+                choices: ["Inspect the grandfather clock.", 2, "Dance"],
+            )"#;
+        let playthrough: Playthrough = ron::from_str(script).unwrap();
+        let err = playthrough.run(&content).unwrap_err();
+        assert!(matches!(&err, PlaythroughError::UnknownChoice { step: 3, dialogue, .. } if dialogue == "BrokenClock"));
+
+        let script = r#"#![enable(implicit_some)]
+            Playthrough(
+                dice: [(6, 6), (1, 1)],
+                choices: ["Inspect the grandfather clock.", "What stories could you tell me, old man?"],
+                expect: (dialogue: "MockingClock", xp: 0),
+            )"#;
+        let playthrough: Playthrough = ron::from_str(script).unwrap();
+        let err = playthrough.run(&content).unwrap_err().to_string();
+        assert!(err.contains("dialogue: expected Some(\"MockingClock\"), got Some(\"HungryClock\")"), "{}", err);
+        assert!(err.contains("1 dice rolls were never used"), "{}", err);
+    }
+}
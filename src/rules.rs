@@ -0,0 +1,137 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dialogues::{read_ron, ContentError};
+use crate::time::Duration;
+
+// How checks are decided, from <content dir>/rules.ron. Challenges and passive checks both go through here,
+// so changing a number there changes the odds shown on options, the rolls and the skill voices together.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub passive_bonus: i32, // Passive checks don't roll; they count as the skill plus this
+    pub critical_success: Option<(i32, i32)>, // A roll that passes whatever the numbers say
+    pub critical_failure: Option<(i32, i32)>, // And one that fails
+    pub travel_time: Duration, // Going through an exit on the map, before the time the room's entry dialogue takes
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self { passive_bonus: 6, critical_success: Some((6, 6)), critical_failure: Some((1, 1)), travel_time: Duration::from_minutes(5) }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outcome {
+    pub success: bool,
+    pub critical: bool,
+}
+
+impl Rules {
+    pub fn load(content_dir: impl AsRef<Path>) -> Result<Self, ContentError> {
+        let path = content_dir.as_ref().join("rules.ron");
+        let rules: Rules = read_ron(&path)?;
+        rules.check().map_err(|message| ContentError::Invalid { path, message })?;
+        Ok(rules)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        for dice in [self.critical_success, self.critical_failure].into_iter().flatten() {
+            if !(1..=6).contains(&dice.0) || !(1..=6).contains(&dice.1) {
+                return Err(format!("critical roll {:?} isn't a pair of d6", dice));
+            }
+        }
+        // Either order of the faces is the same roll, as `is_roll` sees it
+        let sorted = |roll: Option<(i32, i32)>| roll.map(|(die1, die2)| (die1.min(die2), die1.max(die2)));
+        if self.critical_success.is_some() && sorted(self.critical_success) == sorted(self.critical_failure) {
+            return Err("the same roll can't be a critical success and a critical failure".to_string());
+        }
+        Ok(())
+    }
+
+    fn is_roll(roll: Option<(i32, i32)>, dice: (i32, i32)) -> bool {
+        roll.is_some_and(|roll| roll == dice || roll == (dice.1, dice.0))
+    }
+
+    // A challenge: the dice plus `bonus` (skill and modifiers) against `target`, unless the roll is critical
+    pub fn resolve(&self, dice: (i32, i32), bonus: i32, target: i32) -> Outcome {
+        if Self::is_roll(self.critical_success, dice) {
+            Outcome { success: true, critical: true }
+        } else if Self::is_roll(self.critical_failure, dice) {
+            Outcome { success: false, critical: true }
+        } else {
+            Outcome { success: dice.0 + dice.1 + bonus >= target, critical: false }
+        }
+    }
+
+    pub fn passive_succeeds(&self, skill: i32, target: i32) -> bool {
+        skill + self.passive_bonus >= target
+    }
+
+    // Chance that a challenge with `bonus` meets `target`, counting all 36 rolls
+    pub fn success_chance(&self, bonus: i32, target: i32) -> f64 {
+        let mut successes = 0;
+        for die1 in 1..=6 {
+            for die2 in 1..=6 {
+                if self.resolve((die1, die2), bonus, target).success {
+                    successes += 1;
+                }
+            }
+        }
+        successes as f64 / 36.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chances_follow_2d6_with_criticals() {
+        let rules = Rules::default();
+        // Needing 7 on the dice is 21 of 36 rolls
+        assert_eq!(rules.success_chance(5, 12), 21.0 / 36.0);
+        // Nothing can fail but double ones, nothing can pass but double sixes
+        assert_eq!(rules.success_chance(20, 10), 35.0 / 36.0);
+        assert_eq!(rules.success_chance(0, 30), 1.0 / 36.0);
+
+        let rules = Rules { critical_success: None, critical_failure: None, ..Rules::default() };
+        assert_eq!(rules.success_chance(20, 10), 1.0);
+        assert_eq!(rules.success_chance(0, 30), 0.0);
+    }
+
+    #[test]
+    fn criticals_decide_whatever_the_numbers_say() {
+        let rules = Rules::default();
+        assert_eq!(rules.resolve((6, 6), -20, 10), Outcome { success: true, critical: true });
+        assert_eq!(rules.resolve((1, 1), 20, 10), Outcome { success: false, critical: true });
+        assert_eq!(rules.resolve((4, 5), 1, 10), Outcome { success: true, critical: false });
+        assert_eq!(rules.resolve((4, 4), 1, 10), Outcome { success: false, critical: false });
+
+        // Any pair of faces will do, in either order
+        let rules = Rules { critical_success: Some((5, 6)), ..Rules::default() };
+        assert!(rules.resolve((6, 5), -20, 10).critical);
+        assert!(!rules.resolve((6, 6), -20, 10).success);
+    }
+
+    #[test]
+    fn passive_checks_add_the_bonus() {
+        let rules = Rules::default();
+        assert!(rules.passive_succeeds(3, 9));
+        assert!(!rules.passive_succeeds(3, 10));
+        assert!(Rules { passive_bonus: 7, ..rules }.passive_succeeds(3, 10));
+    }
+
+    #[test]
+    fn shipped_rules_load_and_bad_ones_are_refused() {
+        let rules = Rules::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap();
+        assert_eq!(rules, Rules::default());
+
+        assert!(Rules { critical_success: Some((0, 6)), ..Rules::default() }.check().is_err());
+        assert!(Rules { critical_failure: Some((6, 6)), ..Rules::default() }.check().is_err());
+        assert!(Rules { critical_success: Some((5, 6)), critical_failure: Some((6, 5)), ..Rules::default() }.check().is_err());
+        // Durations can't be negative anywhere
+        assert!(ron::from_str::<Rules>("(travel_time: -5)").is_err());
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::dialogues::Content;
+use crate::dice::DiceState;
+use crate::engine::Engine;
+use crate::history::History;
+use crate::player::Player;
+use crate::time::Time;
+use crate::world::{option_key, WorldState};
+
+// Bump this whenever SaveGame changes shape, and teach `load` to upgrade the older versions
+pub const SAVE_VERSION: u32 = 6;
+pub const SAVE_DIR: &str = "saves";
+pub const AUTOSAVE_SLOT: &str = "autosave";
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub player: Player,
+    pub current_location_id: String,
+    pub current_dialogue_id: Option<String>,
+    pub current_time: Time,
+    #[serde(default)] // Missing from version 1 saves, see `migrate_v1`
+    pub world: WorldState,
+    #[serde(default)] // Saves before version 3 keep whatever dice the game already has
+    pub dice: Option<DiceState>,
+    #[serde(default)] // Saves before version 6 start with an empty history
+    pub history: History,
+}
+
+impl SaveGame {
+    pub fn from_engine(engine: &Engine) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            player: engine.player.clone(),
+            current_location_id: engine.location_id.clone(),
+            current_dialogue_id: engine.dialogue_id.clone(),
+            current_time: engine.time,
+            world: engine.world.clone(),
+            dice: Some(engine.dice_state()),
+            history: engine.history.clone(),
+        }
+    }
+
+    // Puts the engine back where the save was made, keeping its content and dice
+    pub fn restore(self, engine: &mut Engine) {
+        engine.player = self.player;
+        engine.location_id = self.current_location_id;
+        engine.dialogue_id = self.current_dialogue_id;
+        // Saves from before the clock rolled over properly can have hours past 23
+        engine.time = Time::from_minutes(self.current_time.minutes());
+        engine.world = self.world;
+        engine.history = self.history;
+        // Passive checks have had their say, and it's in the history
+        engine.interjections.clear();
+        engine.announcements.clear();
+        if let Some(dice) = self.dice {
+            engine.set_dice_state(dice);
+        }
+    }
+}
+
+// Version 1 kept flags and visited dialogues on the player, and recorded picked-up options
+// as removals replayed in order, so `index` is the position at the moment it was removed.
+#[derive(Deserialize)]
+struct V1Save {
+    player: V1Player,
+    #[serde(default)]
+    removed_options: Vec<V1RemovedOption>,
+}
+
+#[derive(Deserialize)]
+struct V1Player {
+    #[serde(default)]
+    dialogues_entered: HashSet<String>,
+    #[serde(default)]
+    flags: HashSet<String>,
+}
+
+#[derive(Deserialize)]
+struct V1RemovedOption {
+    location: String,
+    dialogue: String,
+    index: usize,
+}
+
+fn migrate_v1(old: V1Save, content: &Content) -> WorldState {
+    let mut world = WorldState::default();
+    for flag in &old.player.flags {
+        world.set_flag(flag);
+    }
+
+    // Dialogues were remembered by ID alone, so count one as visited wherever it appears
+    for (location_id, location) in &content.locations {
+        for dialogue_id in location.dialogues.keys() {
+            if old.player.dialogues_entered.contains(dialogue_id) {
+                world.mark_visited(location_id, dialogue_id);
+            }
+        }
+    }
+
+    // Replay the removals against the original positions to find which options they were
+    let mut remaining: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    for removal in &old.removed_options {
+        let Some(dialogue) = content.dialogue(&removal.location, &removal.dialogue) else { continue };
+        let positions = remaining
+            .entry((removal.location.clone(), removal.dialogue.clone()))
+            .or_insert_with(|| (0..dialogue.options.len()).collect());
+        if removal.index < positions.len() {
+            let original = positions.remove(removal.index);
+            let key = option_key(&removal.dialogue, original, &dialogue.options[original]);
+            world.consume(&removal.location, key);
+        }
+    }
+    world
+}
+
+// Up to version 3 the player had a field for each of the four attributes and sixteen skills
+#[derive(Deserialize)]
+struct V3Save {
+    player: V3Player,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct V3Player {
+    tech: i32,
+    arts: i32,
+    bur: i32,
+    und: i32,
+    checkmate_mod: i32,
+    rocketry_mod: i32,
+    pathology_mod: i32,
+    civic_engineering_mod: i32,
+    apparatchik_mod: i32,
+    quota_mod: i32,
+    robot_mod: i32,
+    dossier_mod: i32,
+    delusion_mod: i32,
+    arts2_mod: i32,
+    arts3_mod: i32,
+    arts4_mod: i32,
+    gunsmoke_mod: i32,
+    prohibition_mod: i32,
+    gizmo_mod: i32,
+    oldtime_religion_mod: i32,
+}
+
+// The old fields become entries keyed by their IDs in skills.ron
+fn migrate_v3(old: V3Save, player: &mut Player) {
+    let p = old.player;
+    let attributes = [("tech", p.tech), ("arts", p.arts), ("bureaucracy", p.bur), ("underworld", p.und)];
+    player.attributes = attributes.into_iter().map(|(id, value)| (id.to_string(), value)).collect();
+
+    let skills = [
+        ("checkmate", p.checkmate_mod),
+        ("rocketry", p.rocketry_mod),
+        ("pathology", p.pathology_mod),
+        ("civic_engineering", p.civic_engineering_mod),
+        ("apparatchik", p.apparatchik_mod),
+        ("quota", p.quota_mod),
+        ("robot", p.robot_mod),
+        ("dossier", p.dossier_mod),
+        ("delusion", p.delusion_mod),
+        ("arts2", p.arts2_mod),
+        ("arts3", p.arts3_mod),
+        ("arts4", p.arts4_mod),
+        ("gunsmoke", p.gunsmoke_mod),
+        ("prohibition", p.prohibition_mod),
+        ("gizmo", p.gizmo_mod),
+        ("oldtime_religion", p.oldtime_religion_mod),
+    ];
+    player.skills = skills.into_iter().filter(|(_, points)| *points != 0).map(|(id, points)| (id.to_string(), points)).collect();
+}
+
+// Version 4 still had the placeholder Arts skills
+const V4_SKILL_RENAMES: [(&str, &str); 3] = [("arts2", "transcendence"), ("arts3", "agitprop"), ("arts4", "samizdat")];
+
+fn migrate_v4(player: &mut Player) {
+    for (old, new) in V4_SKILL_RENAMES {
+        if let Some(points) = player.skills.remove(old) {
+            player.skills.insert(new.to_string(), points);
+        }
+        for modifier in player.temporary_modifiers.iter_mut().filter(|modifier| modifier.skill == old) {
+            modifier.skill = new.to_string();
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, message: String },
+    NewerVersion { path: PathBuf, version: u32 },
+    BadSlotName(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            SaveError::Parse { path, message } => write!(f, "{}: {}", path.display(), message),
+            SaveError::NewerVersion { path, version } => write!(
+                f,
+                "{} was saved by a newer version of the game (save version {}, this build reads up to {})",
+                path.display(),
+                version,
+                SAVE_VERSION
+            ),
+            SaveError::BadSlotName(name) => {
+                write!(f, "\"{}\" can't be used as a save name (letters, numbers, spaces, - and _ only)", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+fn slot_path(dir: &Path, slot: &str) -> Result<PathBuf, SaveError> {
+    let valid = !slot.trim().is_empty()
+        && slot.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_');
+    if !valid {
+        return Err(SaveError::BadSlotName(slot.to_string()));
+    }
+    Ok(dir.join(format!("{}.ron", slot.trim())))
+}
+
+pub fn save(dir: impl AsRef<Path>, slot: &str, game: &SaveGame) -> Result<(), SaveError> {
+    let path = slot_path(dir.as_ref(), slot)?;
+    fs::create_dir_all(dir.as_ref()).map_err(|source| SaveError::Io { path: dir.as_ref().to_path_buf(), source })?;
+    let text = ron::ser::to_string_pretty(game, PrettyConfig::new()).expect("save games always serialize");
+    fs::write(&path, text).map_err(|source| SaveError::Io { path, source })
+}
+
+pub fn load(dir: impl AsRef<Path>, slot: &str, content: &Content) -> Result<SaveGame, SaveError> {
+    let path = slot_path(dir.as_ref(), slot)?;
+    let text = fs::read_to_string(&path).map_err(|source| SaveError::Io { path: path.clone(), source })?;
+
+    // Read the version on its own first, so a save from a newer build gets a clear message instead of a parse error
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+    let version = ron::from_str::<Version>(&text)
+        .map(|v| v.version)
+        .map_err(|err| SaveError::Parse { path: path.clone(), message: err.to_string() })?;
+    if version > SAVE_VERSION {
+        return Err(SaveError::NewerVersion { path, version });
+    }
+
+    let parse_error = |err: ron::error::SpannedError| SaveError::Parse { path: path.clone(), message: err.to_string() };
+    let mut game: SaveGame = ron::from_str(&text).map_err(parse_error)?;
+    if version == 1 {
+        game.world = migrate_v1(ron::from_str(&text).map_err(parse_error)?, content);
+    }
+    if version <= 3 {
+        migrate_v3(ron::from_str(&text).map_err(parse_error)?, &mut game.player);
+    }
+    if version <= 4 {
+        migrate_v4(&mut game.player);
+    }
+    game.version = SAVE_VERSION;
+    Ok(game)
+}
+
+// Slot names in the save directory, most recently written first
+pub fn list_slots(dir: impl AsRef<Path>) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else { return vec![] };
+    let mut slots: Vec<(std::time::SystemTime, String)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().and_then(|ext| ext.to_str()) == Some("ron"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+            let name = entry.path().file_stem()?.to_str()?.to_string();
+            Some((modified, name))
+        })
+        .collect();
+    slots.sort_by(|a, b| b.cmp(a));
+    slots.into_iter().map(|(_, name)| name).collect()
+}
+
+pub fn delete(dir: impl AsRef<Path>, slot: &str) -> Result<(), SaveError> {
+    let path = slot_path(dir.as_ref(), slot)?;
+    fs::remove_file(&path).map_err(|source| SaveError::Io { path, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoryEntry;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("shadow_soldiers_{}_{}", name, std::process::id()))
+    }
+
+    fn content() -> Content {
+        Content::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_world_state() {
+        let dir = temp_dir("saves");
+        let mut player = Player::default();
+        player.items.push("Toy Plane".to_string());
+
+        let mut world = WorldState::default();
+        world.set_flag("met_clock");
+        world.consume("Vestibule", "ClockPlaneFail/take_toy_plane".to_string());
+        let game = SaveGame {
+            version: SAVE_VERSION,
+            player,
+            current_location_id: "Vestibule".to_string(),
+            current_dialogue_id: Some("ClockInterior".to_string()),
+            current_time: Time { day: 1, hour: 4, minute: 2 },
+            world: world.clone(),
+            dice: Some(DiceState::Seeded { seed: 7, rolls: 3 }),
+            history: History { entries: vec![HistoryEntry::Choice("Take the toy plane".to_string())] },
+        };
+        save(&dir, "before the clock", &game).unwrap();
+        assert_eq!(list_slots(&dir), vec!["before the clock".to_string()]);
+
+        let loaded = load(&dir, "before the clock", &content()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.player.items, vec!["Toy Plane".to_string()]);
+        assert_eq!(loaded.current_time, Time { day: 1, hour: 4, minute: 2 });
+        assert_eq!(loaded.world, world);
+        assert_eq!(loaded.dice, Some(DiceState::Seeded { seed: 7, rolls: 3 }));
+        assert_eq!(loaded.history, game.history);
+    }
+
+    #[test]
+    fn migrates_version_1_saves() {
+        let dir = temp_dir("v1_saves");
+        fs::create_dir_all(&dir).unwrap();
+        let v1 = r#"(
+            version: 1,
+            player: (
+                tech: 3, arts: 3, bur: 3, und: 3,
+                checkmate_mod: 0, rocketry_mod: 0, pathology_mod: 0, civic_engineering_mod: 0,
+                apparatchik_mod: 2, quota_mod: 0, robot_mod: 0, dossier_mod: 0,
+                delusion_mod: 0, arts2_mod: 1, arts3_mod: 0, arts4_mod: 0,
+                gunsmoke_mod: 0, prohibition_mod: 0, gizmo_mod: 0, oldtime_religion_mod: 0,
+                items: ["Toy Plane"], xp: 10, skill_points: 0,
+                dialogues_entered: ["Start", "InspectClock"],
+                flags: ["met_clock"],
+            ),
+            current_location_id: "Vestibule",
+            current_dialogue_id: Some("Start"),
+            current_time: (day: 1, hour: 4, minute: 2),
+            removed_options: [(location: "Vestibule", dialogue: "ClockPlaneFail", index: 0)],
+        )"#;
+        fs::write(dir.join("old.ron"), v1).unwrap();
+
+        let loaded = load(&dir, "old", &content()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.version, SAVE_VERSION);
+        assert!(loaded.world.has_flag("met_clock"));
+        assert!(loaded.world.has_visited("Vestibule", "InspectClock"));
+        assert!(loaded.world.is_consumed("Vestibule", "ClockPlaneFail/take_toy_plane"));
+        assert!(!loaded.world.is_consumed("Vestibule", "ClockPlaneFail/1"));
+        assert_eq!(loaded.dice, None);
+        assert_eq!(loaded.player.attribute("bureaucracy"), 3);
+        assert_eq!(loaded.player.skills, [("apparatchik".to_string(), 2), ("transcendence".to_string(), 1)].into());
+    }
+
+    #[test]
+    fn rejects_newer_saves_and_bad_slot_names() {
+        let dir = temp_dir("newer_saves");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("future.ron"), "(version: 99, player: ())").unwrap();
+
+        let err = load(&dir, "future", &content()).err().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(err, SaveError::NewerVersion { version: 99, .. }));
+        assert!(matches!(load(&dir, "../escape", &content()).err().unwrap(), SaveError::BadSlotName(_)));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dialogues::{read_ron, ContentError, Effect, Location};
+use crate::skills::SkillRegistry;
+use crate::time::{Duration, Time};
+
+// Things that happen at set times whatever the player is doing, from <content dir>/events.ron.
+// The engine fires them as the clock passes them, see `Engine::pass_time`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub events: Vec<ScheduledEvent>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduledEvent {
+    pub id: String,
+    pub at: Option<Time>,          // The first time it happens; without `every`, the only one
+    pub every: Option<Duration>,   // Repeats this often, from `at` or else from midnight on Day 1, so "1h" is on the hour
+    pub location: Option<String>,  // Only announced there, though the effects apply anywhere
+    pub speaker: Option<String>,   // Who says `text`; None for the narrator
+    pub text: Option<String>,      // Shown alongside the dialogue when it happens, if the player is there to see it
+    pub effects: Vec<Effect>,      // No moves or time, which would fire more events in the middle of these
+}
+
+impl ScheduledEvent {
+    // The last time it happens after `from` and no later than `until`. Passing several at once, say an hourly
+    // chime over a long rest, fires it the once.
+    pub fn happens_between(&self, from: Time, until: Time) -> Option<Time> {
+        let first = self.at.map_or(0, |at| at.minutes());
+        let (from, until) = (from.minutes(), until.minutes());
+        if until < first || until <= from {
+            return None;
+        }
+        let last = match self.every.filter(|every| every.minutes > 0) {
+            Some(every) => first + (until - first) / every.minutes * every.minutes,
+            None => first,
+        };
+        (last > from).then(|| Time::from_minutes(last))
+    }
+}
+
+impl Schedule {
+    // Fails on an event that can't happen, or that refers to a location or skill the rest of the content doesn't have
+    pub fn load(content_dir: impl AsRef<Path>, locations: &HashMap<String, Location>, skills: &SkillRegistry) -> Result<Self, ContentError> {
+        let path = content_dir.as_ref().join("events.ron");
+        let schedule: Schedule = read_ron(&path)?;
+        schedule.check(locations, skills).map_err(|message| ContentError::Invalid { path, message })?;
+        Ok(schedule)
+    }
+
+    fn check(&self, locations: &HashMap<String, Location>, skills: &SkillRegistry) -> Result<(), String> {
+        for (i, event) in self.events.iter().enumerate() {
+            if self.events[..i].iter().any(|other| other.id == event.id) {
+                return Err(format!("{}: there's already an event with this ID", event.id));
+            }
+            match event.every {
+                None if event.at.is_none() => return Err(format!("{}: needs `at`, `every` or both", event.id)),
+                Some(every) if every.minutes == 0 => return Err(format!("{}: can't happen every 0m", event.id)),
+                _ => {}
+            }
+            if let Some(location) = event.location.as_ref().filter(|location| !locations.contains_key(*location)) {
+                return Err(format!("{}: there's no location \"{}\"", event.id, location));
+            }
+            for effect in &event.effects {
+                match effect {
+                    Effect::MoveTo(_) | Effect::AdvanceTime(_) => {
+                        return Err(format!("{}: {} can't happen on a schedule", event.id, effect));
+                    }
+                    Effect::ModifySkill { skill, .. } if !skills.contains(skill) => {
+                        return Err(format!("{}: effect uses unknown skill \"{}\"", event.id, skill));
+                    }
+                    Effect::ModifySkill { duration, .. } if duration.is_zero() => {
+                        return Err(format!("{}: modifies a skill for 0m", event.id));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // What happens after `from` up to and including `until`, in the order it happens
+    pub fn due(&self, from: Time, until: Time) -> Vec<&ScheduledEvent> {
+        let mut due: Vec<_> = self.events.iter().filter_map(|event| Some((event.happens_between(from, until)?, event))).collect();
+        due.sort_by_key(|(time, _)| *time);
+        due.into_iter().map(|(_, event)| event).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialogues::load_locations;
+
+    fn time(day: i32, hour: i32, minute: i32) -> Time {
+        Time { day, hour, minute }
+    }
+
+    #[test]
+    fn events_happen_once_as_the_clock_passes_them() {
+        let once = ScheduledEvent { id: "butler".to_string(), at: Some(time(1, 6, 0)), ..Default::default() };
+        assert_eq!(once.happens_between(time(1, 5, 0), time(1, 6, 0)), Some(time(1, 6, 0)));
+        assert_eq!(once.happens_between(time(1, 6, 0), time(1, 7, 0)), None);
+        assert_eq!(once.happens_between(time(1, 3, 30), time(3, 0, 0)), Some(time(1, 6, 0)));
+
+        let hourly = ScheduledEvent { id: "chime".to_string(), every: Some("1h".parse().unwrap()), ..Default::default() };
+        assert_eq!(hourly.happens_between(time(1, 3, 30), time(1, 3, 59)), None);
+        assert_eq!(hourly.happens_between(time(1, 3, 30), time(1, 4, 0)), Some(time(1, 4, 0)));
+        // Only the last of several hours
+        assert_eq!(hourly.happens_between(time(1, 3, 30), time(1, 7, 15)), Some(time(1, 7, 0)));
+
+        let nightly = ScheduledEvent { at: Some(time(1, 22, 0)), every: Some("1d".parse().unwrap()), ..hourly.clone() };
+        assert_eq!(nightly.happens_between(time(1, 3, 30), time(1, 21, 0)), None);
+        assert_eq!(nightly.happens_between(time(2, 21, 0), time(2, 22, 30)), Some(time(2, 22, 0)));
+
+        let schedule = Schedule { events: vec![hourly, once] };
+        // In the order they happen, not the order they're listed
+        let due: Vec<_> = schedule.due(time(1, 5, 50), time(1, 7, 30)).into_iter().map(|event| event.id.as_str()).collect();
+        assert_eq!(due, ["butler", "chime"]);
+    }
+
+    #[test]
+    fn shipped_events_load_and_bad_ones_are_refused() {
+        let content = concat!(env!("CARGO_MANIFEST_DIR"), "/content");
+        let (locations, skills) = (load_locations(content).unwrap(), SkillRegistry::load(content).unwrap());
+        let schedule = Schedule::load(content, &locations, &skills).unwrap();
+        assert!(schedule.events.iter().any(|event| event.every.is_some()));
+
+        let check = |text: &str| ron::from_str::<Schedule>(text).unwrap().check(&locations, &skills);
+        assert_eq!(check(r#"(events: [(id: "a")])"#), Err("a: needs `at`, `every` or both".to_string()));
+        assert_eq!(check(r#"(events: [(id: "a", every: Some(0))])"#), Err("a: can't happen every 0m".to_string()));
+        assert_eq!(check(r#"(events: [(id: "a", every: Some(5), location: Some("Cellar"))])"#), Err("a: there's no location \"Cellar\"".to_string()));
+        assert!(check(r#"(events: [(id: "a", every: Some(5), effects: [MoveTo("Vestibule")])])"#).is_err());
+        assert!(check(r#"(events: [(id: "a", every: Some(5)), (id: "a", every: Some(10))])"#).is_err());
+        assert!(check(r#"(events: [(id: "a", at: Some((day: 1, hour: 6, minute: 0)), effects: [SetFlag("b")])])"#).is_ok());
+    }
+}
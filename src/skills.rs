@@ -0,0 +1,158 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dialogues::{read_ron, ContentError};
+
+// Every attribute and skill in the game, from <content dir>/skills.ron. Nothing else in the code
+// knows their names: content refers to them by ID and the player's values are keyed the same way.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SkillRegistry {
+    pub attributes: Vec<Attribute>,
+    pub skills: Vec<Skill>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attribute {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: String, // Shown before the name in the GUI
+    #[serde(default = "default_colour")]
+    pub colour: (u8, u8, u8), // What its skills' voices are written in
+    pub description: String, // The hover tooltip
+}
+
+fn default_colour() -> (u8, u8, u8) {
+    (200, 200, 200)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Skill {
+    pub id: String, // snake_case, e.g. "civic_engineering"
+    pub name: String,
+    #[serde(default)]
+    pub icon: String,
+    pub attribute: String, // ID of the attribute it grows out of
+    pub description: String,
+}
+
+impl Attribute {
+    // "🎨 Arts"
+    pub fn label(&self) -> String {
+        icon_label(&self.icon, &self.name)
+    }
+}
+
+impl Skill {
+    pub fn label(&self) -> String {
+        icon_label(&self.icon, &self.name)
+    }
+}
+
+fn icon_label(icon: &str, name: &str) -> String {
+    if icon.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", icon, name)
+    }
+}
+
+impl SkillRegistry {
+    pub fn load(content_dir: impl AsRef<Path>) -> Result<Self, ContentError> {
+        let path = content_dir.as_ref().join("skills.ron");
+        let registry: SkillRegistry = read_ron(&path)?;
+        registry.check().map_err(|message| ContentError::Invalid { path, message })?;
+        Ok(registry)
+    }
+
+    // IDs have to be unique snake_case, and every skill needs an attribute that exists
+    fn check(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        let all_ids = self.attributes.iter().map(|attribute| &attribute.id).chain(self.skills.iter().map(|skill| &skill.id));
+        for id in all_ids {
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+                return Err(format!("\"{}\" isn't a snake_case ID", id));
+            }
+            if !ids.insert(id) {
+                return Err(format!("\"{}\" is defined twice", id));
+            }
+        }
+        for skill in &self.skills {
+            if self.attribute(&skill.attribute).is_none() {
+                return Err(format!("skill \"{}\" belongs to unknown attribute \"{}\"", skill.id, skill.attribute));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn attribute(&self, id: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|attribute| attribute.id == id)
+    }
+
+    pub fn skill(&self, id: &str) -> Option<&Skill> {
+        self.skills.iter().find(|skill| skill.id == id)
+    }
+
+    pub fn contains(&self, skill_id: &str) -> bool {
+        self.skill(skill_id).is_some()
+    }
+
+    // The skills under an attribute, in the order skills.ron lists them
+    pub fn skills_of<'a>(&'a self, attribute_id: &'a str) -> impl Iterator<Item = &'a Skill> {
+        self.skills.iter().filter(move |skill| skill.attribute == attribute_id)
+    }
+
+    // Skills speak in their attribute's colour
+    pub fn colour(&self, skill_id: &str) -> (u8, u8, u8) {
+        self.skill(skill_id).and_then(|skill| self.attribute(&skill.attribute)).map_or(default_colour(), |attribute| attribute.colour)
+    }
+
+    // What to call a skill on screen; the ID itself if it isn't registered
+    pub fn name<'a>(&'a self, skill_id: &'a str) -> &'a str {
+        self.skill(skill_id).map_or(skill_id, |skill| skill.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_registry_loads() {
+        let registry = SkillRegistry::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap();
+        assert_eq!(registry.attributes.len(), 4);
+        assert_eq!(registry.skills.len(), 16);
+        assert_eq!(registry.name("civic_engineering"), "Civic Engineering");
+        assert_eq!(registry.skills_of("underworld").count(), 4);
+        let arts: Vec<String> = registry.skills_of("arts").map(Skill::label).collect();
+        assert_eq!(arts, ["👁 Delusion", "✨ Transcendence", "📢 Agitprop", "📜 Samizdat"]);
+        assert_eq!(registry.colour("samizdat"), registry.attribute("arts").unwrap().colour);
+        assert!(!registry.contains("civic engineering"));
+    }
+
+    #[test]
+    fn registry_ids_are_checked() {
+        let registry: SkillRegistry = ron::from_str(
+            r#"SkillRegistry(
+                attributes: [Attribute(id: "tech", name: "Tech", description: "")],
+                skills: [Skill(id: "rocketry", name: "Rocketry", attribute: "arts", description: "")],
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(registry.check(), Err("skill \"rocketry\" belongs to unknown attribute \"arts\"".to_string()));
+
+        let registry = SkillRegistry {
+            attributes: vec![Attribute {
+                id: "Tech".to_string(),
+                name: "Tech".to_string(),
+                icon: String::new(),
+                colour: default_colour(),
+                description: String::new(),
+            }],
+            skills: vec![],
+        };
+        assert_eq!(registry.check(), Err("\"Tech\" isn't a snake_case ID".to_string()));
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_HOUR: i32 = 60;
+const MINUTES_PER_DAY: i32 = 24 * MINUTES_PER_HOUR;
+
+// A moment on the game clock. Days count from 1 and the hour and minute always stay in range,
+// so comparing the fields in order (day, hour, minute) compares the moments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Time {
+    pub day: i32,
+    pub hour: i32,
+    pub minute: i32,
+}
+
+impl Time {
+    // `minutes` after midnight at the start of Day 1
+    pub fn from_minutes(minutes: i32) -> Self {
+        let time_of_day = minutes.rem_euclid(MINUTES_PER_DAY);
+        Self {
+            day: minutes.div_euclid(MINUTES_PER_DAY) + 1,
+            hour: time_of_day / MINUTES_PER_HOUR,
+            minute: time_of_day % MINUTES_PER_HOUR,
+        }
+    }
+
+    pub fn minutes(&self) -> i32 {
+        (self.day - 1) * MINUTES_PER_DAY + self.hour * MINUTES_PER_HOUR + self.minute
+    }
+
+    pub fn phase(&self) -> DayPhase {
+        DayPhase::of(self.hour)
+    }
+}
+
+impl Add<Duration> for Time {
+    type Output = Time;
+
+    fn add(self, duration: Duration) -> Time {
+        Time::from_minutes(self.minutes() + duration.minutes)
+    }
+}
+
+impl AddAssign<Duration> for Time {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub for Time {
+    type Output = Duration;
+
+    fn sub(self, earlier: Time) -> Duration {
+        Duration::from_minutes(self.minutes() - earlier.minutes())
+    }
+}
+
+// "Day 1, 03:30"
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {}, {:02}:{:02}", self.day, self.hour, self.minute)
+    }
+}
+
+// The part of the day a time falls in, for showing and for content that only happens at some of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPhase {
+    Night,     // 00:00 to 05:59
+    Morning,   // 06:00 to 11:59
+    Afternoon, // 12:00 to 17:59
+    Evening,   // 18:00 to 23:59
+}
+
+impl DayPhase {
+    pub const ALL: [DayPhase; 4] = [DayPhase::Night, DayPhase::Morning, DayPhase::Afternoon, DayPhase::Evening];
+
+    pub fn of(hour: i32) -> Self {
+        match hour {
+            6..=11 => DayPhase::Morning,
+            12..=17 => DayPhase::Afternoon,
+            18..=23 => DayPhase::Evening,
+            _ => DayPhase::Night,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DayPhase::Night => "night",
+            DayPhase::Morning => "morning",
+            DayPhase::Afternoon => "afternoon",
+            DayPhase::Evening => "evening",
+        }
+    }
+}
+
+impl fmt::Display for DayPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// A length of game time. Content writes it as plain minutes, `time: 15`, or with units, `time: "2h30m"`;
+// either way it can't be negative. Durations of an hour or more are saved with units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "DurationFile", into = "DurationFile")]
+pub struct Duration {
+    pub minutes: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DurationFile {
+    Minutes(i32),
+    Text(String),
+}
+
+impl Duration {
+    pub const fn from_minutes(minutes: i32) -> Self {
+        Self { minutes }
+    }
+}
+
+impl Duration {
+    // What's left after `other`, stopping at 0m
+    pub fn saturating_sub(self, other: Duration) -> Duration {
+        Duration::from_minutes((self.minutes - other.minutes).max(0))
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.minutes == 0
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, other: Duration) -> Duration {
+        Duration::from_minutes(self.minutes + other.minutes)
+    }
+}
+
+// "1d2h30m", "45m", or "0m" for no time at all
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.minutes < 0 {
+            write!(f, "-")?;
+        }
+        let minutes = self.minutes.abs();
+        let parts = [(minutes / MINUTES_PER_DAY, "d"), (minutes % MINUTES_PER_DAY / MINUTES_PER_HOUR, "h"), (minutes % MINUTES_PER_HOUR, "m")];
+        let mut written = false;
+        for (amount, unit) in parts {
+            if amount > 0 {
+                write!(f, "{}{}", amount, unit)?;
+                written = true;
+            }
+        }
+        if !written {
+            write!(f, "0m")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DurationError(pub String);
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+// Minutes on their own ("90"), or amounts of days, hours and minutes in that order ("1d 2h", "2h30m")
+impl FromStr for Duration {
+    type Err = DurationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if let Ok(minutes) = text.parse::<i32>() {
+            return Duration::try_from(DurationFile::Minutes(minutes));
+        }
+
+        let error = || DurationError(format!("\"{}\" isn't a duration like 45m, 2h30m or 1d", text));
+        let units = [("d", MINUTES_PER_DAY), ("h", MINUTES_PER_HOUR), ("m", 1)];
+        let mut rest = text;
+        let mut next_unit = 0;
+        let mut minutes: i32 = 0;
+        while !rest.is_empty() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(error)?;
+            let amount: i32 = rest[..digits].parse().map_err(|_| error())?;
+            rest = &rest[digits..];
+            // Each unit once, biggest first
+            let unit = units[next_unit..].iter().position(|(suffix, _)| rest.starts_with(suffix)).ok_or_else(error)? + next_unit;
+            minutes = amount.checked_mul(units[unit].1).and_then(|amount| minutes.checked_add(amount)).ok_or_else(error)?;
+            rest = rest[1..].trim_start();
+            next_unit = unit + 1;
+        }
+        if next_unit == 0 {
+            return Err(error());
+        }
+        Ok(Duration::from_minutes(minutes))
+    }
+}
+
+impl TryFrom<DurationFile> for Duration {
+    type Error = DurationError;
+
+    fn try_from(file: DurationFile) -> Result<Self, Self::Error> {
+        match file {
+            DurationFile::Minutes(minutes) if minutes < 0 => {
+                Err(DurationError(format!("{} minutes would run the clock backwards", minutes)))
+            }
+            DurationFile::Minutes(minutes) => Ok(Duration::from_minutes(minutes)),
+            DurationFile::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl From<Duration> for DurationFile {
+    fn from(duration: Duration) -> Self {
+        if duration.minutes < MINUTES_PER_HOUR {
+            DurationFile::Minutes(duration.minutes)
+        } else {
+            DurationFile::Text(duration.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn time_test() {
+        let mut x = Time { day: 3, hour: 8, minute: 56 };
+
+        x += Duration::from_minutes(675);
+
+        // 11 hours 15, which should roll over
+        assert_eq!(x, Time { day: 3, hour: 20, minute: 11 });
+
+        // Past midnight, more than once
+        x += "2d5h".parse().unwrap();
+        assert_eq!(x, Time { day: 6, hour: 1, minute: 11 });
+        assert_eq!(x.to_string(), "Day 6, 01:11");
+        assert_eq!(x.phase(), DayPhase::Night);
+    }
+
+    #[test]
+    fn durations_parse_and_print_with_units() {
+        let parse = |text: &str| text.parse::<Duration>().map(|duration| duration.minutes);
+        assert_eq!(parse("2h30m"), Ok(150));
+        assert_eq!(parse("1d 2h"), Ok(1560));
+        assert_eq!(parse(" 45m "), Ok(45));
+        assert_eq!(parse("90"), Ok(90));
+        assert!(parse("30m2h").is_err());
+        assert!(parse("2h2h").is_err());
+        assert!(parse("h").is_err());
+        assert!(parse("-5").is_err());
+        assert!(parse("").is_err());
+
+        assert_eq!(Duration::from_minutes(1560).to_string(), "1d2h");
+        assert_eq!(Duration::from_minutes(0).to_string(), "0m");
+
+        // Content can use either form, and short durations stay plain numbers when saved
+        let durations: Vec<Duration> = ron::from_str(r#"[15, "2h30m"]"#).unwrap();
+        assert_eq!(durations, [Duration::from_minutes(15), Duration::from_minutes(150)]);
+        assert_eq!(ron::to_string(&durations).unwrap(), r#"[15,"2h30m"]"#);
+        assert!(ron::from_str::<Duration>("-5").is_err());
+    }
+
+    #[test]
+    fn phases_split_the_day() {
+        let phases: Vec<_> = [0, 5, 6, 11, 12, 17, 18, 23].into_iter().map(DayPhase::of).collect();
+        assert_eq!(
+            phases,
+            [
+                DayPhase::Night,
+                DayPhase::Night,
+                DayPhase::Morning,
+                DayPhase::Morning,
+                DayPhase::Afternoon,
+                DayPhase::Afternoon,
+                DayPhase::Evening,
+                DayPhase::Evening,
+            ]
+        );
+    }
+
+    fn any_time() -> impl Strategy<Value = Time> {
+        (1..1000i32, 0..24i32, 0..60i32).prop_map(|(day, hour, minute)| Time { day, hour, minute })
+    }
+
+    proptest! {
+        #[test]
+        fn adding_time_always_rolls_over_into_range(time in any_time(), minutes in 0..1_000_000i32) {
+            let later = time + Duration::from_minutes(minutes);
+            prop_assert!((0..24).contains(&later.hour) && (0..60).contains(&later.minute));
+            prop_assert!(later >= time);
+            prop_assert_eq!((later - time).minutes, minutes);
+        }
+
+        #[test]
+        fn adding_in_steps_is_adding_at_once(time in any_time(), first in 0..10_000i32, second in 0..10_000i32) {
+            let (first, second) = (Duration::from_minutes(first), Duration::from_minutes(second));
+            prop_assert_eq!(time + first + second, time + (first + second));
+        }
+
+        #[test]
+        fn ordering_follows_the_clock(earlier in any_time(), later in any_time()) {
+            prop_assert_eq!(earlier.cmp(&later), earlier.minutes().cmp(&later.minutes()));
+        }
+
+        #[test]
+        fn durations_print_the_way_they_parse(minutes in 0..100_000i32) {
+            let duration = Duration::from_minutes(minutes);
+            prop_assert_eq!(duration.to_string().parse::<Duration>(), Ok(duration));
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::dialogues::{location_target, resolve_target, Effect, Location};
+use crate::skills::SkillRegistry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub location: String,
+    pub dialogue: Option<String>, // None when the problem is with the location itself
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &self.dialogue {
+            Some(dialogue) => write!(f, "{}: {}/{}: {}", severity, self.location, dialogue, self.message),
+            None => write!(f, "{}: {}: {}", severity, self.location, self.message),
+        }
+    }
+}
+
+pub fn has_errors(issues: &[Issue]) -> bool {
+    issues.iter().any(|issue| issue.severity == Severity::Error)
+}
+
+// Checks every location against the rest of the content. Issues come back sorted by location and dialogue ID.
+pub fn validate(locations: &HashMap<String, Location>, skills: &SkillRegistry) -> Vec<Issue> {
+    let mut issues = vec![];
+
+    let mut location_ids: Vec<&String> = locations.keys().collect();
+    location_ids.sort();
+    let entries = entry_dialogues(locations);
+
+    for location_id in location_ids {
+        let location = &locations[location_id];
+        let mut report = |severity, dialogue: Option<&str>, message: String| {
+            issues.push(Issue {
+                severity,
+                location: location_id.clone(),
+                dialogue: dialogue.map(str::to_string),
+                message,
+            });
+        };
+
+        for exit in &location.exits {
+            if !locations.contains_key(&exit.to) {
+                report(Severity::Error, None, format!("exit \"{}\" is not a location", exit.to));
+            }
+            for skill in exit.requires.iter().flat_map(|condition| condition.skills()) {
+                if !skills.contains(skill) {
+                    report(Severity::Error, None, format!("exit \"{}\" condition uses unknown skill \"{}\"", exit.to, skill));
+                }
+            }
+            if let Some(target) = &exit.locked_dialogue {
+                let (target_location, target_dialogue) = resolve_target(locations, location_id, target);
+                if !has_dialogue(locations, &target_location, &target_dialogue) {
+                    report(Severity::Error, None, format!("exit \"{}\" locked_dialogue \"{}\" doesn't exist", exit.to, target));
+                }
+            }
+        }
+
+        // A room with nothing to say is fine; arriving there shows its exits
+        if location.dialogues.is_empty() {
+            continue;
+        }
+
+        let mut dialogue_ids: Vec<&String> = location.dialogues.keys().collect();
+        dialogue_ids.sort();
+
+        for dialogue_id in &dialogue_ids {
+            let dialogue = &location.dialogues[*dialogue_id];
+            let here = Some(dialogue_id.as_str());
+
+            if dialogue_id.is_empty() {
+                report(Severity::Warning, here, "dialogue has an empty ID".to_string());
+            }
+            if dialogue.intro.trim().is_empty() {
+                report(Severity::Warning, here, "intro is empty".to_string());
+            }
+
+            let mut option_ids = HashSet::new();
+            for (i, option) in dialogue.options.iter().enumerate() {
+                let option_name = format!("option {}", i + 1);
+
+                // Saves remember used-up options by ID, so two options can't share one
+                if let Some(id) = &option.id {
+                    if !option_ids.insert(id) {
+                        report(Severity::Error, here, format!("{} reuses the option ID \"{}\"", option_name, id));
+                    }
+                }
+
+                if option.description.trim().is_empty() {
+                    report(Severity::Warning, here, format!("{} has an empty description", option_name));
+                }
+
+                for (kind, target) in [("success", &option.success_dialogue), ("failure", &option.failure_dialogue)] {
+                    let Some(target) = target else { continue };
+                    if target.is_empty() {
+                        report(Severity::Error, here, format!("{} has an empty {} target", option_name, kind));
+                    } else if target.contains("::") {
+                        let (target_location, target_dialogue) = location_target(target);
+                        if !has_dialogue(locations, &target_location, &target_dialogue) {
+                            report(Severity::Error, here, format!("{} {} target \"{}\" doesn't exist", option_name, kind, target));
+                        }
+                    } else if !location.dialogues.contains_key(target) && !locations.contains_key(target) {
+                        report(
+                            Severity::Error,
+                            here,
+                            format!("{} {} target \"{}\" is neither a dialogue here nor a location", option_name, kind, target),
+                        );
+                    }
+                }
+
+                if option.challenge_number.is_some() {
+                    if option.failure_dialogue.is_none() {
+                        report(Severity::Error, here, format!("{} is a challenge with no failure_dialogue", option_name));
+                    }
+                    if option.challenge_attribute.is_none() {
+                        report(Severity::Error, here, format!("{} is a challenge with no challenge_attribute", option_name));
+                    }
+                }
+            }
+
+            for (place, skill) in dialogue.skill_references() {
+                if !skills.contains(skill) {
+                    report(Severity::Error, here, format!("{} uses unknown skill \"{}\"", place, skill));
+                }
+            }
+
+            let effects = dialogue.effects.iter()
+                .map(|effect| ("dialogue".to_string(), effect))
+                .chain(dialogue.options.iter().enumerate().flat_map(|(i, option)| {
+                    option.effects.iter().map(move |effect| (format!("option {}", i + 1), effect))
+                }));
+            for (owner, effect) in effects {
+                match effect {
+                    Effect::MoveTo(target) => {
+                        let (target_location, target_dialogue) = location_target(target);
+                        if !locations.contains_key(&target_location) {
+                            report(Severity::Error, here, format!("{} moves to \"{}\", which is not a location", owner, target_location));
+                        } else if target.contains("::") && !has_dialogue(locations, &target_location, &target_dialogue) {
+                            report(Severity::Error, here, format!("{} moves to \"{}\", which doesn't exist", owner, target));
+                        }
+                    }
+                    // It would wear off before it ever counted
+                    Effect::ModifySkill { duration, .. } if duration.is_zero() => {
+                        report(Severity::Error, here, format!("{} modifies a skill for 0m", owner));
+                    }
+                    _ => {}
+                }
+            }
+
+            // Conditions already parsed when the content loaded, but visited(...) can still name a dialogue that isn't there
+            let conditions = dialogue.visible_when.iter()
+                .chain(dialogue.options.iter().filter_map(|option| option.visible_when.as_ref()))
+                .chain(dialogue.passive_check.iter().filter_map(|check| check.visible_when.as_ref()));
+            for condition in conditions {
+                for (visited_location, visited_dialogue) in condition.visits() {
+                    let visited_location = visited_location.unwrap_or(location_id);
+                    if !locations.get(visited_location).is_some_and(|location| location.dialogues.contains_key(visited_dialogue)) {
+                        report(
+                            Severity::Error,
+                            here,
+                            format!("condition \"{}\" visits {}/{}, which doesn't exist", condition, visited_location, visited_dialogue),
+                        );
+                    }
+                }
+            }
+        }
+
+        if !location.dialogues.contains_key("Start") {
+            // Its dialogues can still be reached by qualified targets, but walking in only shows the exits
+            report(Severity::Warning, None, "location has dialogues but no Start, so arriving only shows its exits".to_string());
+            continue;
+        }
+
+        let reachable = reachable_from_start(locations, location_id, entries.get(location_id.as_str()));
+        for dialogue_id in dialogue_ids {
+            if !reachable.contains(dialogue_id.as_str()) {
+                report(Severity::Warning, Some(dialogue_id), "unreachable from Start".to_string());
+            }
+        }
+    }
+
+    issues
+}
+
+fn has_dialogue(locations: &HashMap<String, Location>, location_id: &str, dialogue_id: &str) -> bool {
+    locations.get(location_id).is_some_and(|location| location.dialogues.contains_key(dialogue_id))
+}
+
+// Dialogues other than Start that qualified targets and locked doors lead into, by location
+fn entry_dialogues(locations: &HashMap<String, Location>) -> HashMap<String, Vec<String>> {
+    let mut entries: HashMap<String, Vec<String>> = HashMap::new();
+    for (location_id, location) in locations {
+        for target in location.exits.iter().filter_map(|exit| exit.locked_dialogue.as_ref()) {
+            let (target_location, target_dialogue) = resolve_target(locations, location_id, target);
+            entries.entry(target_location).or_default().push(target_dialogue);
+        }
+        for dialogue in location.dialogues.values() {
+            let moves = dialogue.effects.iter()
+                .chain(dialogue.options.iter().flat_map(|option| &option.effects))
+                .filter_map(|effect| match effect {
+                    Effect::MoveTo(target) => Some(target),
+                    _ => None,
+                });
+            let targets = dialogue.options.iter()
+                .flat_map(|option| [&option.success_dialogue, &option.failure_dialogue].into_iter().flatten())
+                .chain(moves);
+            for target in targets.filter(|target| target.contains("::")) {
+                let (location_id, dialogue_id) = location_target(target);
+                entries.entry(location_id).or_default().push(dialogue_id);
+            }
+        }
+    }
+    entries
+}
+
+fn reachable_from_start(locations: &HashMap<String, Location>, location_id: &str, entries: Option<&Vec<String>>) -> HashSet<String> {
+    let mut reachable = HashSet::new();
+    let mut queue: VecDeque<String> = VecDeque::from(["Start".to_string()]);
+    queue.extend(entries.into_iter().flatten().cloned());
+
+    while let Some(id) = queue.pop_front() {
+        let Some(dialogue) = locations[location_id].dialogues.get(&id) else { continue };
+        if !reachable.insert(id) {
+            continue;
+        }
+        for option in &dialogue.options {
+            for target in [&option.success_dialogue, &option.failure_dialogue].into_iter().flatten() {
+                let (target_location, target_dialogue) = resolve_target(locations, location_id, target);
+                if target_location == location_id {
+                    queue.push_back(target_dialogue);
+                }
+            }
+        }
+    }
+
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialogues::{Dialogue, DialogueOption};
+    use crate::time::Duration;
+
+    fn skills() -> SkillRegistry {
+        SkillRegistry::load(concat!(env!("CARGO_MANIFEST_DIR"), "/content")).unwrap()
+    }
+
+    fn messages(issues: &[Issue]) -> Vec<String> {
+        issues.iter().map(|issue| issue.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_dangling_unreachable_and_unknown_skills() {
+        let mut hall = Location::new("Hall".to_string());
+        hall.add_dialogue(
+            "Start".to_string(),
+            Dialogue {
+                intro: "A hall.".to_string(),
+                options: vec![
+                    DialogueOption {
+                        description: "Knock".to_string(),
+                        challenge_attribute: Some("civic engineering".to_string()),
+                        challenge_number: Some(8),
+                        success_dialogue: Some("Nowhere".to_string()),
+                        ..Default::default()
+                    },
+                    DialogueOption {
+                        description: "Leave".to_string(),
+                        success_dialogue: Some("".to_string()),
+                        effects: vec![Effect::ModifySkill {
+                            skill: "gizmo".to_string(),
+                            amount: 1,
+                            duration: Duration::from_minutes(0),
+                            source: "Draught".to_string(),
+                        }],
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        hall.add_dialogue(
+            "Orphan".to_string(),
+            Dialogue { intro: "".to_string(), visible_when: Some("visited(Attic)".parse().unwrap()), ..Default::default() },
+        );
+
+        let locations = HashMap::from([("Hall".to_string(), hall)]);
+        let issues = validate(&locations, &skills());
+
+        assert_eq!(
+            messages(&issues),
+            vec![
+                "warning: Hall/Orphan: intro is empty",
+                "error: Hall/Orphan: condition \"visited(Attic)\" visits Hall/Attic, which doesn't exist",
+                "error: Hall/Start: option 1 success target \"Nowhere\" is neither a dialogue here nor a location",
+                "error: Hall/Start: option 1 is a challenge with no failure_dialogue",
+                "error: Hall/Start: option 2 has an empty success target",
+                "error: Hall/Start: option 1 challenge uses unknown skill \"civic engineering\"",
+                "error: Hall/Start: option 2 modifies a skill for 0m",
+                "warning: Hall/Orphan: unreachable from Start",
+            ]
+        );
+        assert!(has_errors(&issues));
+    }
+
+    #[test]
+    fn location_targets_are_not_dangling() {
+        let mut hall = Location::new("Hall".to_string());
+        hall.add_exit("Yard".to_string());
+        hall.add_dialogue(
+            "Start".to_string(),
+            Dialogue {
+                intro: "A hall.".to_string(),
+                options: vec![DialogueOption {
+                    description: "Go outside.".to_string(),
+                    success_dialogue: Some("Yard".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        let mut yard = Location::new("Yard".to_string());
+        yard.add_dialogue(
+            "Start".to_string(),
+            Dialogue { intro: "A yard.".to_string(), ..Default::default() },
+        );
+
+        let locations = HashMap::from([("Hall".to_string(), hall), ("Yard".to_string(), yard)]);
+        assert_eq!(validate(&locations, &skills()), vec![]);
+    }
+
+    #[test]
+    fn qualified_targets_name_a_dialogue_in_a_location() {
+        let mut hall = Location::new("Hall".to_string());
+        hall.add_dialogue(
+            "Start".to_string(),
+            Dialogue {
+                intro: "A hall.".to_string(),
+                options: vec![
+                    DialogueOption {
+                        description: "Call out to the yard.".to_string(),
+                        success_dialogue: Some("Yard::Echo".to_string()),
+                        ..Default::default()
+                    },
+                    DialogueOption {
+                        description: "Go to the shed.".to_string(),
+                        effects: vec![Effect::MoveTo("Yard::Shed".to_string())],
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        let mut yard = Location::new("Yard".to_string());
+        yard.add_dialogue("Start".to_string(), Dialogue { intro: "A yard.".to_string(), ..Default::default() });
+        // Only reachable from the hall, which counts
+        yard.add_dialogue("Echo".to_string(), Dialogue { intro: "Hello-o.".to_string(), ..Default::default() });
+
+        let locations = HashMap::from([("Hall".to_string(), hall), ("Yard".to_string(), yard)]);
+        assert_eq!(
+            messages(&validate(&locations, &skills())),
+            ["error: Hall/Start: option 2 moves to \"Yard::Shed\", which doesn't exist"]
+        );
+    }
+
+    #[test]
+    fn rooms_without_a_start_are_walked_into() {
+        let mut hall = Location::new("Hall".to_string());
+        hall.add_exit("Landing".to_string());
+        hall.add_exit("Attic".to_string());
+        hall.add_dialogue(
+            "Start".to_string(),
+            Dialogue {
+                intro: "A hall.".to_string(),
+                options: vec![DialogueOption {
+                    description: "Climb up.".to_string(),
+                    success_dialogue: Some("Attic::Trunk".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        let mut attic = Location::new("Attic".to_string());
+        attic.add_dialogue("Trunk".to_string(), Dialogue { intro: "A trunk.".to_string(), options: vec![], ..Default::default() });
+        let locations = HashMap::from([
+            ("Hall".to_string(), hall),
+            ("Landing".to_string(), Location::new("Landing".to_string())),
+            ("Attic".to_string(), attic),
+        ]);
+
+        // The empty landing is fine; the attic has something to say, but not on the way in
+        let issues = validate(&locations, &skills());
+        assert_eq!(messages(&issues), ["warning: Attic: location has dialogues but no Start, so arriving only shows its exits"]);
+        assert!(!has_errors(&issues));
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dialogues::DialogueOption;
+
+// Stable ID of an option within its location: "<dialogue>/<option id>", falling back to the
+// option's position in the content file when the writer hasn't given it an `id`
+pub fn option_key(dialogue_id: &str, index: usize, option: &DialogueOption) -> String {
+    match &option.id {
+        Some(id) => format!("{}/{}", dialogue_id, id),
+        None => format!("{}/{}", dialogue_id, index),
+    }
+}
+
+// A passive check within its location: "<dialogue>/passive/<position>"
+pub fn passive_key(dialogue_id: &str, index: usize) -> String {
+    format!("{}/passive/{}", dialogue_id, index)
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationState {
+    pub visited_dialogues: BTreeSet<String>,
+    pub consumed_options: BTreeSet<String>, // Option keys, see `option_key`
+    #[serde(default)]
+    pub attempted_checks: BTreeMap<String, i32>, // Option key -> skill value when the check was failed or, if red, tried
+    #[serde(default)]
+    pub heard_passives: BTreeSet<String>, // Passive checks that have spoken, see `passive_key`
+    #[serde(default)]
+    pub arrived: bool, // The player has been here, even if there was no dialogue to enter
+}
+
+// Everything a playthrough changes, kept apart from the content it plays over.
+// A new game is just `WorldState::default()`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldState {
+    pub flags: BTreeSet<String>,
+    pub locations: BTreeMap<String, LocationState>,
+}
+
+impl WorldState {
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.insert(flag.to_string());
+    }
+
+    pub fn has_visited(&self, location_id: &str, dialogue_id: &str) -> bool {
+        self.locations
+            .get(location_id)
+            .is_some_and(|location| location.visited_dialogues.contains(dialogue_id))
+    }
+
+    pub fn has_visited_location(&self, location_id: &str) -> bool {
+        self.locations
+            .get(location_id)
+            .is_some_and(|location| location.arrived || !location.visited_dialogues.is_empty())
+    }
+
+    pub fn mark_arrived(&mut self, location_id: &str) {
+        self.location_mut(location_id).arrived = true;
+    }
+
+    // Returns true the first time a dialogue is entered
+    pub fn mark_visited(&mut self, location_id: &str, dialogue_id: &str) -> bool {
+        self.location_mut(location_id).visited_dialogues.insert(dialogue_id.to_string())
+    }
+
+    pub fn is_consumed(&self, location_id: &str, option_key: &str) -> bool {
+        self.locations
+            .get(location_id)
+            .is_some_and(|location| location.consumed_options.contains(option_key))
+    }
+
+    pub fn consume(&mut self, location_id: &str, option_key: String) {
+        self.location_mut(location_id).consumed_options.insert(option_key);
+    }
+
+    // The skill value a locked check was attempted with, if it's been attempted
+    pub fn check_attempt(&self, location_id: &str, option_key: &str) -> Option<i32> {
+        self.locations.get(location_id)?.attempted_checks.get(option_key).copied()
+    }
+
+    pub fn record_check(&mut self, location_id: &str, option_key: String, skill_value: i32) {
+        self.location_mut(location_id).attempted_checks.insert(option_key, skill_value);
+    }
+
+    pub fn forget_check(&mut self, location_id: &str, option_key: &str) {
+        self.location_mut(location_id).attempted_checks.remove(option_key);
+    }
+
+    pub fn has_heard(&self, location_id: &str, passive_key: &str) -> bool {
+        self.locations
+            .get(location_id)
+            .is_some_and(|location| location.heard_passives.contains(passive_key))
+    }
+
+    pub fn mark_heard(&mut self, location_id: &str, passive_key: String) {
+        self.location_mut(location_id).heard_passives.insert(passive_key);
+    }
+
+    fn location_mut(&mut self, location_id: &str) -> &mut LocationState {
+        self.locations.entry(location_id.to_string()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_are_consumed_by_id_or_position() {
+        let mut world = WorldState::default();
+        let named = DialogueOption { id: Some("take_letter".to_string()), ..Default::default() };
+        let unnamed = DialogueOption::default();
+
+        world.consume("Vestibule", option_key("Mailbox400", 1, &named));
+        world.consume("Vestibule", option_key("Mailbox400", 0, &unnamed));
+        assert!(world.is_consumed("Vestibule", "Mailbox400/take_letter"));
+        assert!(world.is_consumed("Vestibule", "Mailbox400/0"));
+        assert!(!world.is_consumed("FirstFloor", "Mailbox400/0"));
+
+        assert!(world.mark_visited("Vestibule", "Start"));
+        assert!(!world.mark_visited("Vestibule", "Start"));
+        assert!(world.has_visited_location("Vestibule"));
+        assert!(!world.has_visited_location("Garden"));
+    }
+}